@@ -12,9 +12,164 @@ use std::path::PathBuf;
 use anyhow::Context as _;
 use anyhow::Error;
 use anyhow::Result;
-#[cfg(feature = "deploy")]
 use anyhow::anyhow;
 
+use toml::Table;
+
+
+/// Retrieve the string stored under `key` in `table`.
+fn toml_str<'table>(table: &'table Table, key: &str, path: &Path) -> Result<&'table str> {
+    table
+        .get(key)
+        .with_context(|| format!("`{}`: `{key}` is missing", path.display()))?
+        .as_str()
+        .with_context(|| format!("`{}`: `{key}` is not a string", path.display()))
+}
+
+/// Remove `;` comments from the query source code `src`.
+fn strip_query_comments(src: &str) -> String {
+    let mut stripped = String::with_capacity(src.len());
+    for line in src.lines() {
+        let mut in_str = false;
+        let mut escaped = false;
+        for c in line.chars() {
+            match c {
+                ';' if !in_str => break,
+                '"' if !escaped => in_str = !in_str,
+                _ => (),
+            }
+            escaped = c == '\\' && !escaped;
+            let () = stripped.push(c);
+        }
+        let () = stripped.push('\n');
+    }
+    stripped
+}
+
+/// Extract the severity declared by means of `severity` properties in
+/// the query source code `src` of a lint, returning the name of the
+/// corresponding `Severity` variant.
+///
+/// The query itself is compiled and checked at runtime; this function
+/// only has to agree with that on well-formed queries, which is
+/// verified by tests.
+fn extract_severity(src: &str, path: &Path) -> Result<&'static str> {
+    let src = strip_query_comments(src);
+    let mut severity = None;
+    for (idx, _) in src.match_indices("\"severity\"") {
+        let value = src[idx + "\"severity\"".len()..]
+            .trim_start()
+            .strip_prefix('"')
+            .and_then(|rest| rest.split_once('"'))
+            .map(|(value, _)| value)
+            .with_context(|| {
+                format!("`{}`: `severity` property has no value set", path.display())
+            })?;
+        let value = match value {
+            "hint" => "Hint",
+            "info" => "Info",
+            "warning" => "Warning",
+            "error" => "Error",
+            _ => {
+                return Err(anyhow!(
+                    "`{}`: `severity` property `{value}` is invalid",
+                    path.display()
+                ))
+            },
+        };
+        match severity {
+            Some(severity) if severity != value => {
+                return Err(anyhow!(
+                    "`{}`: patterns declare conflicting `severity` properties",
+                    path.display()
+                ))
+            },
+            _ => severity = Some(value),
+        }
+    }
+    Ok(severity.unwrap_or("Warning"))
+}
+
+/// Generate the Rust source code for the meta data of a lint, as
+/// contained in the TOML file at `meta_path`.
+fn generate_lint_meta(meta_path: &Path) -> Result<String> {
+    let meta = read_to_string(meta_path)
+        .with_context(|| format!("failed to read lint meta data `{}`", meta_path.display()))?;
+    let meta = meta
+        .parse::<Table>()
+        .with_context(|| format!("failed to parse lint meta data `{}`", meta_path.display()))?;
+
+    let summary = toml_str(&meta, "summary", meta_path)?.trim();
+    let rationale = toml_str(&meta, "rationale", meta_path)?.trim();
+    let references = meta
+        .get("references")
+        .map(|references| {
+            references
+                .as_array()
+                .with_context(|| {
+                    format!("`{}`: `references` is not an array", meta_path.display())
+                })?
+                .iter()
+                .map(|reference| {
+                    let reference = reference.as_str().with_context(|| {
+                        format!("`{}`: reference is not a string", meta_path.display())
+                    })?;
+                    Ok(format!(r####"r###"{reference}"###, "####))
+                })
+                .collect::<Result<String>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let examples = meta
+        .get("examples")
+        .map(|examples| {
+            examples
+                .as_array()
+                .with_context(|| format!("`{}`: `examples` is not an array", meta_path.display()))?
+                .iter()
+                .map(|example| {
+                    let example = example.as_table().with_context(|| {
+                        format!("`{}`: example is not a table", meta_path.display())
+                    })?;
+                    let bad = toml_str(example, "bad", meta_path)?.trim_end();
+                    let good = toml_str(example, "good", meta_path)?.trim_end();
+                    Ok(format!(r####"(r###"{bad}"###, r###"{good}"###), "####))
+                })
+                .collect::<Result<String>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let enabled = meta
+        .get("enabled")
+        .map(|enabled| {
+            enabled
+                .as_bool()
+                .with_context(|| format!("`{}`: `enabled` is not a boolean", meta_path.display()))
+        })
+        .transpose()?
+        .unwrap_or(true);
+
+    if let Some(key) = meta.keys().find(|key| {
+        !matches!(
+            key.as_str(),
+            "summary" | "rationale" | "references" | "examples" | "enabled"
+        )
+    }) {
+        return Err(anyhow!(
+            "`{}`: encountered unexpected key `{key}`",
+            meta_path.display()
+        ))
+    }
+
+    let meta = format!(
+        r####"summary: r###"{summary}"###,
+    rationale: r###"{rationale}"###,
+    references: &[{references}],
+    examples: &[{examples}],
+    enabled: {enabled},"####
+    );
+    Ok(meta)
+}
 
 fn generate_lints(manifest_dir: &Path) -> Result<()> {
     let out_dir =
@@ -49,18 +204,25 @@ fn generate_lints(manifest_dir: &Path) -> Result<()> {
             )
         })?;
         let lint_name = lint_name.trim_end_matches(".scm");
+        let lint_meta = generate_lint_meta(&lint_path.with_extension("toml"))?;
+        let lint_severity = extract_severity(&lint_src, &lint_path)?;
         let lint_name_upper = lint_name.to_ascii_uppercase().replace('-', "_");
-        let lint_var = format!("LINT_{lint_name_upper}_SRC");
+        let lint_var = format!("LINT_{lint_name_upper}");
         writeln!(
             &mut lints_rs_file,
-            r####"pub static {lint_var}: (&str, &str) = (r###"{lint_name}"###, r###"{lint_src}"###);"####
+            r####"pub static {lint_var}: BuiltinLint = BuiltinLint {{
+    name: r###"{lint_name}"###,
+    src: r###"{lint_src}"###,
+    severity: Severity::{lint_severity},
+    {lint_meta}
+}};"####
         )?;
         let () = lint_vars.push(lint_var);
     }
 
     writeln!(
         &mut lints_rs_file,
-        r#"pub static LINTS: [(&str, &str); {}] = ["#,
+        r#"pub static LINTS: [BuiltinLint; {}] = ["#,
         lint_vars.len()
     )?;
     for lint_var in lint_vars {
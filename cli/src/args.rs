@@ -2,12 +2,17 @@ use std::fs::File;
 use std::io::BufRead as _;
 use std::io::BufReader;
 use std::path::PathBuf;
+use std::str::FromStr as _;
 
 use anyhow::Context as _;
 use anyhow::Result;
 
 use clap::ArgAction;
 use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+
+use bpflint::Severity;
 
 
 fn parse_files(s: &str) -> Result<Vec<PathBuf>> {
@@ -29,16 +34,102 @@ fn parse_files(s: &str) -> Result<Vec<PathBuf>> {
     }
 }
 
+fn parse_severity(s: &str) -> Result<(String, Severity)> {
+    let (lint, severity) = s
+        .split_once('=')
+        .with_context(|| format!("`{s}` is not of the form <LINT>=<SEVERITY>"))?;
+    let severity = Severity::from_str(severity)?;
+    Ok((lint.to_string(), severity))
+}
+
+fn parse_deny(s: &str) -> Result<Deny> {
+    // Severities are accepted in plural form as well, e.g., "warnings".
+    let severity = s.strip_suffix('s').unwrap_or(s);
+    let deny = match Severity::from_str(severity) {
+        Ok(severity) => Deny::Severity(severity),
+        Err(_) if s == "none" => Deny::None,
+        Err(_) => Deny::Lint(s.to_string()),
+    };
+    Ok(deny)
+}
+
 
 /// A command line interface for `bpflint`.
 #[derive(Debug, Parser)]
-#[command(version = env!("VERSION"))]
+#[command(
+    version = env!("VERSION"),
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
     /// The BPF C source files to lint.
     ///
     /// Use '@file' syntax to include a file list contained in 'file'.
     #[arg(required = true, value_name = "[@]SRCS", value_parser = parse_files)]
     pub srcs: Vec<Vec<PathBuf>>,
+    /// Use the configuration file at the provided path for all sources.
+    ///
+    /// By default, the closest 'bpflint.toml' file is searched for each
+    /// source file, starting in its directory and moving up.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    #[command(flatten)]
+    pub linter: LinterArgs,
+    /// The format in which to report lint matches.
+    #[arg(long, value_enum, default_value_t = Format::Terminal)]
+    pub format: Format,
+    /// When to use colors in terminal output.
+    ///
+    /// By default, colors are used if stdout is a terminal and the
+    /// `NO_COLOR` environment variable is not set.
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+    /// The number of lines of context to show around matches in
+    /// terminal output.
+    #[arg(short = 'C', long, value_name = "N", default_value_t = 0)]
+    pub context: usize,
+    /// Exit with a failure status if matches of at least the provided
+    /// severity or of the provided lint are reported.
+    ///
+    /// Unless a severity (or 'none') is provided, matches with 'error'
+    /// severity are denied. Can be supplied multiple times.
+    #[arg(long, value_name = "SEVERITY|LINT", value_parser = parse_deny)]
+    pub deny: Vec<Deny>,
+    /// Exit with a failure status if code that could not be parsed is
+    /// encountered.
+    ///
+    /// This option enables and denies the 'syntax-error' lint.
+    #[arg(long)]
+    pub deny_syntax_errors: bool,
+    /// Only print aggregate statistics about lint matches, instead of
+    /// reporting them individually.
+    ///
+    /// Statistics are printed as a JSON object when used together with
+    /// `--format json` and as a table otherwise.
+    #[arg(long)]
+    pub stats: bool,
+    /// Only report matches that are not recorded in the provided
+    /// baseline file.
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+    /// Record all matches in the provided baseline file instead of
+    /// reporting them.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["baseline", "stats"])]
+    pub write_baseline: Option<PathBuf>,
+    /// Only report matches on lines added or modified by the provided
+    /// unified diff (e.g., as produced by `git diff`).
+    ///
+    /// Use '-' to read the diff from stdin.
+    #[arg(long, value_name = "FILE|-", conflicts_with = "write_baseline")]
+    pub diff: Option<PathBuf>,
+    /// The directory that paths in the diff provided via `--diff` are
+    /// relative to (e.g., the repository root).
+    ///
+    /// Defaults to the current working directory.
+    #[arg(long, value_name = "DIR", requires = "diff")]
+    pub diff_root: Option<PathBuf>,
     /// Print a list of available lints.
     #[arg(long, exclusive = true)]
     pub print_lints: bool,
@@ -47,6 +138,115 @@ pub struct Args {
     pub verbosity: u8,
 }
 
+/// The format in which to report lint matches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable, multi-line reports.
+    #[default]
+    Terminal,
+    /// One line per match, in compiler style ('path:line:col: ...').
+    Short,
+    /// One JSON object per line and match.
+    Json,
+    /// A single SARIF 2.1.0 log covering all matches.
+    Sarif,
+    /// GitHub Actions workflow commands, for annotating code.
+    Github,
+}
+
+/// A policy causing lint matches to result in a failure exit status.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Deny {
+    /// Deny no matches based on their severity, overriding the default
+    /// of denying errors.
+    None,
+    /// Deny matches of at least the given severity.
+    Severity(Severity),
+    /// Deny matches of the lint with the given name.
+    Lint(String),
+}
+
+/// A choice of when to use colors in output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Use colors when writing to a terminal.
+    #[default]
+    Auto,
+    /// Always use colors.
+    Always,
+    /// Never use colors.
+    Never,
+}
+
+/// Options controlling the set of lints to check for.
+#[derive(Debug, Default, clap::Args)]
+pub struct LinterArgs {
+    /// Load an additional lint from a 'tree-sitter' query file.
+    ///
+    /// The lint is named after the file, without extension. Can be
+    /// supplied multiple times.
+    #[arg(long = "lint-file", value_name = "FILE")]
+    pub lint_files: Vec<PathBuf>,
+    /// Load additional lints from all '*.scm' files in a directory.
+    ///
+    /// Can be supplied multiple times.
+    #[arg(long = "lint-dir", value_name = "DIR")]
+    pub lint_dirs: Vec<PathBuf>,
+    /// Overwrite the severity with which a lint is reported.
+    ///
+    /// SEVERITY is one of 'hint', 'info', 'warning', or 'error'. Can be
+    /// supplied multiple times.
+    #[arg(long = "severity", value_name = "LINT=SEVERITY", value_parser = parse_severity)]
+    pub severities: Vec<(String, Severity)>,
+    /// Only run the provided lints.
+    ///
+    /// Multiple lints can be separated by comma. Can be supplied
+    /// multiple times.
+    #[arg(long, value_name = "LINT", value_delimiter = ',')]
+    pub only: Vec<String>,
+    /// Run the provided lints, even if excluded otherwise.
+    ///
+    /// Multiple lints can be separated by comma. Can be supplied
+    /// multiple times.
+    #[arg(long, value_name = "LINT", value_delimiter = ',')]
+    pub enable: Vec<String>,
+    /// Do not run the provided lints.
+    ///
+    /// Multiple lints can be separated by comma. Can be supplied
+    /// multiple times.
+    #[arg(long, value_name = "LINT", value_delimiter = ',')]
+    pub disable: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Explain a lint in detail.
+    Explain(Explain),
+    /// Run a Language Server Protocol server, communicating via stdio.
+    Lsp(Lsp),
+}
+
+/// A type representing the `explain` command.
+#[derive(Debug, Parser)]
+pub struct Explain {
+    /// The name of the lint to explain.
+    pub lint: String,
+}
+
+/// A type representing the `lsp` command.
+#[derive(Debug, Parser)]
+pub struct Lsp {
+    /// Use the configuration file at the provided path for all
+    /// documents.
+    ///
+    /// By default, the closest 'bpflint.toml' file is searched for each
+    /// document, starting in its directory and moving up.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    #[command(flatten)]
+    pub linter: LinterArgs,
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -109,4 +309,246 @@ mod tests {
             ]
         );
     }
+
+    /// Check that we can parse the `explain` sub-command.
+    #[test]
+    fn explain_parsing() {
+        let args = Args::try_parse_from(["executable", "explain", "probe-read"]).unwrap();
+        assert!(args.srcs.is_empty());
+        assert!(matches!(
+            args.command,
+            Some(Command::Explain(Explain { lint })) if lint == "probe-read"
+        ));
+
+        let _err = Args::try_parse_from(["executable", "explain"]).unwrap_err();
+    }
+
+    /// Check that we can parse the `lsp` command.
+    #[test]
+    fn lsp_parsing() {
+        let args = Args::try_parse_from(["executable", "lsp"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Lsp(Lsp { config: None, .. }))
+        ));
+
+        let args = Args::try_parse_from([
+            "executable",
+            "lsp",
+            "--config=bpflint.toml",
+            "--disable=foo",
+        ])
+        .unwrap();
+        let Some(Command::Lsp(Lsp { config, linter })) = args.command else {
+            panic!("unexpected command: {:?}", args.command)
+        };
+        assert_eq!(config, Some(PathBuf::from("bpflint.toml")));
+        assert_eq!(linter.disable, vec!["foo".to_string()]);
+    }
+
+    /// Check that we can parse the configuration file option.
+    #[test]
+    fn config_parsing() {
+        let args = Args::try_parse_from(["executable", "foobar"]).unwrap();
+        assert_eq!(args.config, None);
+
+        let args =
+            Args::try_parse_from(["executable", "--config", "bpflint.toml", "foobar"]).unwrap();
+        assert_eq!(args.config, Some(PathBuf::from("bpflint.toml")));
+    }
+
+    /// Check that we can parse custom lint options.
+    #[test]
+    fn custom_lint_parsing() {
+        let args = Args::try_parse_from([
+            "executable",
+            "--lint-file",
+            "foo.scm",
+            "--lint-dir=lints/",
+            "--lint-file=bar.scm",
+            "foobar",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.linter.lint_files,
+            vec![PathBuf::from("foo.scm"), PathBuf::from("bar.scm")]
+        );
+        assert_eq!(args.linter.lint_dirs, vec![PathBuf::from("lints/")]);
+    }
+
+    /// Check that we can parse severity overwrites.
+    #[test]
+    fn severity_parsing() {
+        let args = Args::try_parse_from([
+            "executable",
+            "--severity",
+            "probe-read=error",
+            "--severity=unstable-attach-point=hint",
+            "foobar",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.linter.severities,
+            vec![
+                ("probe-read".to_string(), Severity::Error),
+                ("unstable-attach-point".to_string(), Severity::Hint),
+            ]
+        );
+
+        let _err =
+            Args::try_parse_from(["executable", "--severity", "probe-read", "foobar"]).unwrap_err();
+        let _err = Args::try_parse_from(["executable", "--severity", "probe-read=fatal", "foobar"])
+            .unwrap_err();
+    }
+
+    /// Check that we can parse lint selection options.
+    #[test]
+    fn lint_selection_parsing() {
+        let args = Args::try_parse_from(["executable", "foobar"]).unwrap();
+        assert!(args.linter.only.is_empty());
+        assert!(args.linter.enable.is_empty());
+        assert!(args.linter.disable.is_empty());
+
+        let args = Args::try_parse_from([
+            "executable",
+            "--only",
+            "probe-read,unstable-attach-point",
+            "--only=untyped-map-member",
+            "--enable=foo",
+            "--disable",
+            "bar,baz",
+            "foobar",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.linter.only,
+            ["probe-read", "unstable-attach-point", "untyped-map-member"]
+        );
+        assert_eq!(args.linter.enable, ["foo"]);
+        assert_eq!(args.linter.disable, ["bar", "baz"]);
+    }
+
+    /// Check that we can parse the output format.
+    #[test]
+    fn format_parsing() {
+        let args = Args::try_parse_from(["executable", "foobar"]).unwrap();
+        assert_eq!(args.format, Format::Terminal);
+
+        let args = Args::try_parse_from(["executable", "--format", "json", "foobar"]).unwrap();
+        assert_eq!(args.format, Format::Json);
+
+        let args = Args::try_parse_from(["executable", "--format=sarif", "foobar"]).unwrap();
+        assert_eq!(args.format, Format::Sarif);
+
+        let args = Args::try_parse_from(["executable", "--format=github", "foobar"]).unwrap();
+        assert_eq!(args.format, Format::Github);
+
+        let args = Args::try_parse_from(["executable", "--format=short", "foobar"]).unwrap();
+        assert_eq!(args.format, Format::Short);
+
+        let _err = Args::try_parse_from(["executable", "--format", "xml", "foobar"]).unwrap_err();
+    }
+
+    /// Check that we can parse the color option.
+    #[test]
+    fn color_parsing() {
+        let args = Args::try_parse_from(["executable", "foobar"]).unwrap();
+        assert_eq!(args.color, ColorChoice::Auto);
+
+        let args = Args::try_parse_from(["executable", "--color", "never", "foobar"]).unwrap();
+        assert_eq!(args.color, ColorChoice::Never);
+
+        let args = Args::try_parse_from(["executable", "--color=always", "foobar"]).unwrap();
+        assert_eq!(args.color, ColorChoice::Always);
+
+        let _err = Args::try_parse_from(["executable", "--color=sometimes", "foobar"]).unwrap_err();
+    }
+
+    /// Check that we can parse the context option.
+    #[test]
+    fn context_parsing() {
+        let args = Args::try_parse_from(["executable", "foobar"]).unwrap();
+        assert_eq!(args.context, 0);
+
+        let args = Args::try_parse_from(["executable", "--context", "3", "foobar"]).unwrap();
+        assert_eq!(args.context, 3);
+
+        let args = Args::try_parse_from(["executable", "-C2", "foobar"]).unwrap();
+        assert_eq!(args.context, 2);
+
+        let _err = Args::try_parse_from(["executable", "--context=-1", "foobar"]).unwrap_err();
+    }
+
+    /// Check that we can parse deny policies.
+    #[test]
+    fn deny_parsing() {
+        let args = Args::try_parse_from([
+            "executable",
+            "--deny",
+            "warnings",
+            "--deny=info",
+            "--deny=probe-read",
+            "--deny=none",
+            "foobar",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.deny,
+            vec![
+                Deny::Severity(Severity::Warning),
+                Deny::Severity(Severity::Info),
+                Deny::Lint("probe-read".to_string()),
+                Deny::None,
+            ]
+        );
+        assert!(!args.deny_syntax_errors);
+
+        let args = Args::try_parse_from(["executable", "--deny-syntax-errors", "foobar"]).unwrap();
+        assert!(args.deny_syntax_errors);
+    }
+
+    /// Check that we can parse the baseline options.
+    #[test]
+    fn baseline_parsing() {
+        let args = Args::try_parse_from(["executable", "foobar"]).unwrap();
+        assert_eq!(args.baseline, None);
+        assert_eq!(args.write_baseline, None);
+
+        let args =
+            Args::try_parse_from(["executable", "--baseline", "base.json", "foobar"]).unwrap();
+        assert_eq!(args.baseline, Some(PathBuf::from("base.json")));
+
+        let args =
+            Args::try_parse_from(["executable", "--write-baseline=base.json", "foobar"]).unwrap();
+        assert_eq!(args.write_baseline, Some(PathBuf::from("base.json")));
+
+        let _err = Args::try_parse_from([
+            "executable",
+            "--baseline=old.json",
+            "--write-baseline=new.json",
+            "foobar",
+        ])
+        .unwrap_err();
+    }
+
+    /// Check that we can parse the diff option.
+    #[test]
+    fn diff_parsing() {
+        let args = Args::try_parse_from(["executable", "foobar"]).unwrap();
+        assert_eq!(args.diff, None);
+
+        let args = Args::try_parse_from(["executable", "--diff", "-", "foobar"]).unwrap();
+        assert_eq!(args.diff, Some(PathBuf::from("-")));
+
+        let args = Args::try_parse_from(["executable", "--diff=change.patch", "foobar"]).unwrap();
+        assert_eq!(args.diff, Some(PathBuf::from("change.patch")));
+        assert_eq!(args.diff_root, None);
+
+        let args =
+            Args::try_parse_from(["executable", "--diff=-", "--diff-root=repo", "foobar"]).unwrap();
+        assert_eq!(args.diff_root, Some(PathBuf::from("repo")));
+
+        let result = Args::try_parse_from(["executable", "--diff-root=repo", "foobar"]);
+        assert!(result.is_err());
+    }
 }
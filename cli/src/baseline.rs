@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write as _;
+use std::path::Path;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::ensure;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use bpflint::LintMatch;
+
+
+/// The version of the baseline file format.
+const VERSION: u32 = 1;
+
+
+/// Compute the 64 bit FNV-1a hash of `data`.
+fn fnv1a(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    data.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Create a location independent fingerprint of a lint match.
+///
+/// The fingerprint is derived from the content of the lines spanned by
+/// the match, with all runs of whitespace collapsed, so that it is
+/// unaffected by code being moved around or re-indented.
+fn fingerprint(r#match: &LintMatch, code: &[u8]) -> String {
+    let range = &r#match.range;
+    let mut normalized = Vec::new();
+    if !range.bytes.is_empty() {
+        let words = code
+            .split(|byte| *byte == b'\n')
+            .skip(range.start_point.row)
+            .take(range.end_point.row - range.start_point.row + 1)
+            .flat_map(|line| line.split(u8::is_ascii_whitespace))
+            .filter(|word| !word.is_empty());
+
+        for word in words {
+            if !normalized.is_empty() {
+                let () = normalized.push(b' ');
+            }
+            let () = normalized.extend_from_slice(word);
+        }
+    }
+    format!("{:016x}", fnv1a(&normalized))
+}
+
+
+/// A single finding, as recorded in a baseline.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+struct Finding {
+    /// The name of the lint that matched.
+    lint: String,
+    /// The path to the file in which the match was found.
+    path: String,
+    /// The location independent fingerprint of the match.
+    fingerprint: String,
+}
+
+impl Finding {
+    fn new(path: &Path, r#match: &LintMatch, code: &[u8]) -> Self {
+        Self {
+            lint: r#match.lint_name.clone(),
+            path: path.to_string_lossy().replace('\\', "/"),
+            fingerprint: fingerprint(r#match, code),
+        }
+    }
+}
+
+
+/// The on-disk representation of a [`Baseline`].
+#[derive(Debug, Deserialize, Serialize)]
+struct BaselineFile {
+    version: u32,
+    findings: Vec<Finding>,
+}
+
+
+/// A set of known findings, the matches of which should not be
+/// reported.
+///
+/// The same finding may be recorded multiple times, in which case as
+/// many matching lint matches are considered known.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    /// Findings along with the number of times they were recorded.
+    findings: BTreeMap<Finding, usize>,
+}
+
+impl Baseline {
+    /// Load a baseline from the file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open baseline `{}`", path.display()))?;
+        let baseline = Self::read(BufReader::new(file))
+            .with_context(|| format!("failed to read baseline `{}`", path.display()))?;
+        Ok(baseline)
+    }
+
+    fn read<R>(reader: R) -> Result<Self>
+    where
+        R: io::Read,
+    {
+        let BaselineFile { version, findings } = serde_json::from_reader(reader)?;
+        ensure!(
+            version == VERSION,
+            "baseline version {version} is unsupported; expected {VERSION}"
+        );
+
+        let mut baseline = Self::default();
+        for finding in findings {
+            *baseline.findings.entry(finding).or_default() += 1;
+        }
+        Ok(baseline)
+    }
+
+    /// Save the baseline to the file at `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create baseline `{}`", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        let () = self
+            .write(&mut writer)
+            .and_then(|()| writer.flush().map_err(Into::into))
+            .with_context(|| format!("failed to write baseline `{}`", path.display()))?;
+        Ok(())
+    }
+
+    fn write<W>(&self, mut writer: W) -> Result<()>
+    where
+        W: io::Write,
+    {
+        let findings = self
+            .findings
+            .iter()
+            .flat_map(|(finding, count)| (0..*count).map(move |_| finding.clone()))
+            .collect();
+        let file = BaselineFile {
+            version: VERSION,
+            findings,
+        };
+        let () = serde_json::to_writer_pretty(&mut writer, &file)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    /// Record a lint match found in the file at `path`, with content
+    /// `code`.
+    pub fn add(&mut self, path: &Path, r#match: &LintMatch, code: &[u8]) {
+        *self
+            .findings
+            .entry(Finding::new(path, r#match, code))
+            .or_default() += 1;
+    }
+
+    /// Check whether a lint match found in the file at `path`, with
+    /// content `code`, is known and, if so, consume the corresponding
+    /// finding.
+    pub fn remove(&mut self, path: &Path, r#match: &LintMatch, code: &[u8]) -> bool {
+        let finding = Finding::new(path, r#match, code);
+        match self.findings.get_mut(&finding) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                true
+            },
+            Some(_) => {
+                let _count = self.findings.remove(&finding);
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bpflint::Point;
+    use bpflint::Range;
+    use bpflint::Severity;
+
+
+    fn probe_read(code: &str) -> LintMatch {
+        let start = code.find("bpf_probe_read").unwrap();
+        let end = start + "bpf_probe_read".len();
+        let row = code[..start].matches('\n').count();
+        let col = start - code[..start].rfind('\n').map_or(0, |idx| idx + 1);
+
+        LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "bpf_probe_read() is deprecated".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: start..end,
+                start_point: Point { row, col },
+                end_point: Point {
+                    row,
+                    col: col + end - start,
+                },
+            },
+        }
+    }
+
+    /// Check that fingerprints do not depend on the location of a match
+    /// or the whitespace surrounding it.
+    #[test]
+    fn fingerprinting() {
+        let code1 = "int x;\n\tbpf_probe_read(a, b,  c);\n";
+        let code2 = "\n\n  bpf_probe_read(a, b, c);  \n";
+        let code3 = "\tbpf_probe_read(a, b, d);\n";
+
+        let fp1 = fingerprint(&probe_read(code1), code1.as_bytes());
+        let fp2 = fingerprint(&probe_read(code2), code2.as_bytes());
+        let fp3 = fingerprint(&probe_read(code3), code3.as_bytes());
+        assert_eq!(fp1, fp2);
+        assert_ne!(fp1, fp3);
+    }
+
+    /// Check that findings are consumed from a baseline as expected,
+    /// including after a round trip through its on-disk representation.
+    #[test]
+    fn baseline_filtering() {
+        let path = Path::new("foo.bpf.c");
+        let code = "bpf_probe_read(a, b, c);\n";
+        let m = probe_read(code);
+
+        let mut baseline = Baseline::default();
+        let () = baseline.add(path, &m, code.as_bytes());
+        let () = baseline.add(path, &m, code.as_bytes());
+
+        let mut data = Vec::new();
+        let () = baseline.write(&mut data).unwrap();
+        let mut baseline = Baseline::read(data.as_slice()).unwrap();
+
+        assert!(!baseline.remove(Path::new("bar.bpf.c"), &m, code.as_bytes()));
+        assert!(baseline.remove(path, &m, code.as_bytes()));
+        assert!(baseline.remove(path, &m, code.as_bytes()));
+        assert!(!baseline.remove(path, &m, code.as_bytes()));
+    }
+
+    /// Make sure that we reject baselines of an unknown version.
+    #[test]
+    fn baseline_version_check() {
+        let data = br#"{"version": 1337, "findings": []}"#;
+        let err = Baseline::read(data.as_slice()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "baseline version 1337 is unsupported; expected 1"
+        );
+    }
+}
@@ -0,0 +1,405 @@
+use std::fs::canonicalize;
+use std::fs::read_to_string;
+use std::io;
+use std::io::Read as _;
+use std::ops::Range;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::bail;
+
+use bpflint::LintMatch;
+
+
+/// Parse the path from a `+++` file header line, returning `None` for
+/// deleted files.
+fn parse_path(header: &str) -> Option<PathBuf> {
+    // Timestamps, if present, are separated by a tab.
+    let path = header.split('\t').next().unwrap_or_default().trim_end();
+    let path = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .unwrap_or(path);
+    (path != "/dev/null").then(|| PathBuf::from(path))
+}
+
+/// Parse a `<start>[,<count>]` hunk range.
+fn parse_hunk_range(s: &str) -> Option<(usize, usize)> {
+    let (start, count) = s.split_once(',').unwrap_or((s, "1"));
+    Some((start.parse().ok()?, count.parse().ok()?))
+}
+
+/// Parse a hunk header of the form
+/// `@@ -<start>[,<count>] +<start>[,<count>] @@`, returning the old and
+/// new line ranges.
+fn parse_hunk_header(line: &str) -> Option<((usize, usize), (usize, usize))> {
+    let mut parts = line.strip_prefix("@@ ")?.split(' ');
+    let old = parse_hunk_range(parts.next()?.strip_prefix('-')?)?;
+    let new = parse_hunk_range(parts.next()?.strip_prefix('+')?)?;
+    if parts.next()? != "@@" {
+        return None
+    }
+    Some((old, new))
+}
+
+
+/// A file touched by a diff.
+#[derive(Debug)]
+struct File {
+    /// The path to the file, as it appears in the diff.
+    path: PathBuf,
+    /// The canonical paths of existing files that `path` may refer to.
+    resolved: Vec<PathBuf>,
+    /// Ranges of zero-based rows of added or modified lines.
+    rows: Vec<Range<usize>>,
+}
+
+impl File {
+    /// Resolve the file's path relative to the directory `root`.
+    fn resolve(&mut self, root: &Path) {
+        if self.path.as_os_str().is_empty() {
+            return
+        }
+
+        // Paths in diffs are commonly prefixed with `a/` and `b/`, but
+        // that is not a given (e.g., `git diff --no-prefix`). So check
+        // both with and without prefix.
+        let mut components = self.path.components();
+        let unprefixed = match components.next() {
+            Some(Component::Normal(prefix)) if prefix == "a" || prefix == "b" => {
+                Some(components.as_path())
+            },
+            _ => None,
+        };
+
+        self.resolved = [Some(self.path.as_path()), unprefixed]
+            .into_iter()
+            .flatten()
+            .filter(|path| !path.as_os_str().is_empty())
+            .filter_map(|path| canonicalize(root.join(path)).ok())
+            .collect();
+    }
+
+    fn add_row(&mut self, row: usize) {
+        match self.rows.last_mut() {
+            Some(rows) if rows.end == row => rows.end += 1,
+            _ => self.rows.push(row..row + 1),
+        }
+    }
+
+    /// Check whether the file corresponds to the source file with the
+    /// canonical path `path`.
+    fn matches(&self, path: &Path) -> bool {
+        self.resolved.iter().any(|resolved| resolved == path)
+    }
+}
+
+
+/// The lines added or modified by a unified diff.
+#[derive(Debug, Default)]
+pub struct Diff {
+    files: Vec<File>,
+}
+
+impl Diff {
+    /// Load a unified diff from the file at `path`, with `-` referring
+    /// to stdin.
+    ///
+    /// Paths in the diff are interpreted relative to the directory
+    /// `root`.
+    pub fn load(path: &Path, root: &Path) -> Result<Self> {
+        let root = canonicalize(root)
+            .with_context(|| format!("failed to canonicalize `{}`", root.display()))?;
+        let diff = if path == Path::new("-") {
+            let mut diff = String::new();
+            let _count = io::stdin()
+                .read_to_string(&mut diff)
+                .context("failed to read diff from stdin")?;
+            diff
+        } else {
+            read_to_string(path)
+                .with_context(|| format!("failed to read diff `{}`", path.display()))?
+        };
+
+        let diff = Self::parse(&diff, &root)
+            .with_context(|| format!("failed to parse diff `{}`", path.display()))?;
+        Ok(diff)
+    }
+
+    /// Parse a unified diff, with paths relative to the directory
+    /// `root`, which is expected to be canonical.
+    pub fn parse(diff: &str, root: &Path) -> Result<Self> {
+        let mut files = Vec::<File>::new();
+        // The number of old and new lines remaining in the current
+        // hunk.
+        let mut remaining = (0usize, 0usize);
+        // The zero-based row of the next new line of the current hunk.
+        let mut row = 0;
+
+        for (i, line) in diff.lines().enumerate() {
+            if remaining != (0, 0) {
+                let (old, new) = match line.chars().next() {
+                    Some('+') => (0, 1),
+                    Some('-') => (1, 0),
+                    // Context lines; some tools strip the leading space
+                    // of empty ones.
+                    Some(' ') | None => (1, 1),
+                    // "\ No newline at end of file"
+                    Some('\\') => continue,
+                    Some(_) => bail!("line {}: encountered malformed hunk", i + 1),
+                };
+
+                remaining = match (remaining.0.checked_sub(old), remaining.1.checked_sub(new)) {
+                    (Some(old), Some(new)) => (old, new),
+                    _ => bail!("line {}: encountered malformed hunk", i + 1),
+                };
+
+                if old == 0 {
+                    if let Some(file) = files.last_mut() {
+                        let () = file.add_row(row);
+                    }
+                }
+                row += new;
+                continue
+            }
+
+            if let Some(header) = line.strip_prefix("+++ ") {
+                let path = parse_path(header);
+                // Deleted files have no lines that could be reported on,
+                // so we simply track them with an empty path.
+                let mut file = File {
+                    path: path.unwrap_or_default(),
+                    resolved: Vec::new(),
+                    rows: Vec::new(),
+                };
+                let () = file.resolve(root);
+                let () = files.push(file);
+            } else if line.starts_with("@@ ") {
+                if files.is_empty() {
+                    bail!("line {}: encountered hunk without file header", i + 1)
+                }
+                let ((_, old_count), (new_start, new_count)) = parse_hunk_header(line)
+                    .with_context(|| format!("line {}: hunk header `{line}` is invalid", i + 1))?;
+                remaining = (old_count, new_count);
+                // Empty new ranges have a start line that refers to the
+                // line *before* the hunk, which we never use.
+                row = new_start.saturating_sub(1);
+            }
+            // Everything else (`diff --git`, `index`, `---`, ...) is
+            // irrelevant to us.
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Check whether a lint match in the file at `path` covers any line
+    /// added or modified by the diff.
+    pub fn contains(&self, path: &Path, r#match: &LintMatch) -> bool {
+        let start = r#match.range.start_point.row;
+        let end = r#match.range.end_point.row;
+        let Ok(path) = canonicalize(path) else {
+            return false
+        };
+
+        self.files
+            .iter()
+            .filter(|file| file.matches(&path))
+            .flat_map(|file| file.rows.iter())
+            .any(|rows| rows.start <= end && start < rows.end)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::create_dir_all;
+    use std::fs::write;
+
+    use tempfile::TempDir;
+    use tempfile::tempdir;
+
+    use bpflint::Point;
+    use bpflint::Severity;
+
+
+    /// Create a directory containing a few (empty) source files,
+    /// returning it along with its canonical path.
+    fn source_dir() -> (TempDir, PathBuf) {
+        let dir = tempdir().unwrap();
+        let root = canonicalize(dir.path()).unwrap();
+        for path in ["foo.bpf.c", "src/foo.bpf.c", "src/bar.bpf.c", "b/foo.bpf.c"] {
+            let path = root.join(path);
+            let () = create_dir_all(path.parent().unwrap()).unwrap();
+            let () = write(path, "").unwrap();
+        }
+        (dir, root)
+    }
+
+    fn m(start_row: usize, end_row: usize) -> LintMatch {
+        LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: String::new(),
+            severity: Severity::Warning,
+            range: bpflint::Range {
+                bytes: 0..1,
+                start_point: Point {
+                    row: start_row,
+                    col: 0,
+                },
+                end_point: Point {
+                    row: end_row,
+                    col: 1,
+                },
+            },
+        }
+    }
+
+    /// Check that we can parse a diff without context lines, as
+    /// produced by `git diff -U0`.
+    #[test]
+    fn diff_parsing_no_context() {
+        let diff = r#"diff --git a/src/foo.bpf.c b/src/foo.bpf.c
+index 1234567..89abcde 100644
+--- a/src/foo.bpf.c
++++ b/src/foo.bpf.c
+@@ -3 +3 @@ int x;
+-	bpf_probe_read(a, b, c);
++	bpf_probe_read(a, b, d);
+@@ -10,0 +11,2 @@
+++++ this is not a header
++	bpf_probe_read(e, f, g);
+@@ -20,2 +22,0 @@
+-	foo();
+-	bar();
+diff --git a/gone.bpf.c b/gone.bpf.c
+deleted file mode 100644
+--- a/gone.bpf.c
++++ /dev/null
+@@ -1 +0,0 @@
+-int x;
+"#;
+        let (_dir, root) = source_dir();
+        let diff = Diff::parse(diff, &root).unwrap();
+        assert_eq!(diff.files.len(), 2);
+        assert_eq!(diff.files[0].path, Path::new("b/src/foo.bpf.c"));
+        assert_eq!(diff.files[0].rows, vec![2..3, 10..12]);
+        assert!(diff.files[1].rows.is_empty());
+
+        let path = root.join("src/foo.bpf.c");
+        assert!(!diff.contains(&path, &m(0, 1)));
+        assert!(diff.contains(&path, &m(2, 2)));
+        assert!(diff.contains(&path, &m(1, 2)));
+        assert!(!diff.contains(&path, &m(3, 9)));
+        assert!(diff.contains(&path, &m(11, 11)));
+        assert!(!diff.contains(&path, &m(12, 20)));
+        assert!(!diff.contains(&root.join("src/bar.bpf.c"), &m(2, 2)));
+        assert!(!diff.contains(&root.join("src/gone.bpf.c"), &m(2, 2)));
+    }
+
+    /// Check that we can parse a diff with context lines.
+    #[test]
+    fn diff_parsing_context() {
+        let diff = r#"--- foo.bpf.c	2025-01-01 00:00:00.000000000 +0000
++++ foo.bpf.c	2025-01-02 00:00:00.000000000 +0000
+@@ -1,5 +1,6 @@
+ int x;
+-int y;
++int z;
++int w;
+
+ int main() {
+ }
+\ No newline at end of file
+"#;
+        let (_dir, root) = source_dir();
+        let diff = Diff::parse(diff, &root).unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path, Path::new("foo.bpf.c"));
+        assert_eq!(diff.files[0].rows, vec![1..3]);
+    }
+
+    /// Check that we map paths in a diff to source files as expected.
+    #[test]
+    fn path_matching() {
+        let (_dir, root) = source_dir();
+        let file = |path: &str| {
+            let mut file = File {
+                path: PathBuf::from(path),
+                resolved: Vec::new(),
+                rows: Vec::new(),
+            };
+            let () = file.resolve(&root);
+            file
+        };
+        let matches =
+            |file: File, path: &str| file.matches(&canonicalize(root.join(path)).unwrap());
+
+        assert!(matches(file("b/src/foo.bpf.c"), "src/foo.bpf.c"));
+        assert!(matches(file("b/src/foo.bpf.c"), "./src/foo.bpf.c"));
+        assert!(matches(file("b/src/foo.bpf.c"), "src/../src/foo.bpf.c"));
+        assert!(matches(file("a/src/foo.bpf.c"), "src/foo.bpf.c"));
+        assert!(matches(file("src/foo.bpf.c"), "src/foo.bpf.c"));
+        assert!(!matches(file("b/src/foo.bpf.c"), "foo.bpf.c"));
+        assert!(!matches(file("b/src/foo.bpf.c"), "src/bar.bpf.c"));
+        assert!(!matches(file(""), "foo.bpf.c"));
+        assert!(!matches(file("b"), "foo.bpf.c"));
+
+        // Files with the same name in different directories are not
+        // confused.
+        assert!(matches(file("b/foo.bpf.c"), "foo.bpf.c"));
+        assert!(!matches(file("b/foo.bpf.c"), "src/foo.bpf.c"));
+        assert!(!matches(file("src/foo.bpf.c"), "foo.bpf.c"));
+        // Without prefix, `b/` may just as well be a directory.
+        assert!(matches(file("b/foo.bpf.c"), "b/foo.bpf.c"));
+    }
+
+    /// Check that source files are matched irrespective of the
+    /// directory their paths are relative to, e.g., when running from a
+    /// subdirectory.
+    #[test]
+    fn path_matching_subdirectory() {
+        let text = r#"--- a/src/foo.bpf.c
++++ b/src/foo.bpf.c
+@@ -1 +1 @@
+-int x;
++int y;
+"#;
+        let (_dir, root) = source_dir();
+        let diff = Diff::parse(text, &root).unwrap();
+
+        // The path as it would be provided when running from within
+        // `src/`, made absolute, as we can't change the working
+        // directory of the test process.
+        let path = root.join("src").join("foo.bpf.c");
+        assert!(diff.contains(&path, &m(0, 0)));
+        assert!(!diff.contains(&root.join("foo.bpf.c"), &m(0, 0)));
+
+        // Resolving against the wrong root finds no files.
+        let diff = Diff::parse(text, &root.join("src")).unwrap();
+        assert!(!diff.contains(&path, &m(0, 0)));
+    }
+
+    /// Make sure that we reject malformed diffs.
+    #[test]
+    fn malformed_diff_parsing() {
+        let err = Diff::parse("@@ -1 +1 @@\n-a\n+b\n", Path::new("/")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: encountered hunk without file header"
+        );
+
+        let err = Diff::parse("+++ b/foo\n@@ -1 +x @@\n", Path::new("/")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: hunk header `@@ -1 +x @@` is invalid"
+        );
+
+        let err = Diff::parse("+++ b/foo\n@@ -1 +1 @@\n*a\n", Path::new("/")).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: encountered malformed hunk");
+    }
+}
@@ -0,0 +1,740 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io;
+use std::io::BufRead;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::bail;
+
+use serde_json::Value;
+use serde_json::json;
+
+use tracing::debug;
+
+use bpflint::Config;
+use bpflint::LintMatch;
+use bpflint::Linter;
+use bpflint::Point;
+use bpflint::Severity;
+use bpflint::TextEdit;
+
+use crate::args::LinterArgs;
+use crate::create_linter;
+use crate::explain;
+
+
+/// JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for internal errors.
+const INTERNAL_ERROR: i64 = -32603;
+/// LSP error code for requests received before initialization.
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+/// The `TextDocumentSyncKind` indicating incremental document syncs.
+const SYNC_INCREMENTAL: u8 = 2;
+/// The `MessageType` of error messages.
+const MESSAGE_ERROR: u8 = 1;
+
+
+/// Read a single message from `reader`, returning `None` if the end
+/// of the stream was reached.
+fn receive(reader: &mut dyn BufRead) -> Result<Option<Value>> {
+    let mut len = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None)
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                let value = value.trim();
+                len = Some(
+                    value
+                        .parse::<usize>()
+                        .with_context(|| format!("content length `{value}` is invalid"))?,
+                );
+            }
+        }
+    }
+
+    let len = len.context("message lacks Content-Length header")?;
+    let mut body = vec![0; len];
+    let () = reader.read_exact(&mut body)?;
+    let message = serde_json::from_slice(&body).context("failed to parse message")?;
+    Ok(Some(message))
+}
+
+/// Write a single message to `writer`.
+fn send(writer: &mut dyn io::Write, message: &Value) -> Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    let () = writer.flush()?;
+    Ok(())
+}
+
+/// Convert a `file://` URI into a path.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            let () = decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            let () = decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+/// Convert a [`Point`] with UTF-16 based columns into an LSP position.
+fn position(point: Point) -> Value {
+    json!({"line": point.row, "character": point.col})
+}
+
+/// Convert an LSP position into a [`Point`] with UTF-16 based columns.
+fn point(position: &Value) -> Result<Point> {
+    let field = |name| {
+        position[name]
+            .as_u64()
+            .and_then(|value| usize::try_from(value).ok())
+            .with_context(|| format!("position lacks valid `{name}`"))
+    };
+    Ok(Point {
+        row: field("line")?,
+        col: field("character")?,
+    })
+}
+
+/// Convert a [`Point`] with a UTF-16 based column into a byte offset
+/// into `code`.
+///
+/// Points beyond the end of a line or the code are clamped.
+fn byte_offset(code: &[u8], point: Point) -> usize {
+    let mut start = 0;
+    for _ in 0..point.row {
+        match code[start..].iter().position(|b| *b == b'\n') {
+            Some(idx) => start += idx + 1,
+            None => return code.len(),
+        }
+    }
+
+    let end = code[start..]
+        .iter()
+        .position(|b| *b == b'\n')
+        .map_or(code.len(), |idx| start + idx);
+    let Ok(line) = str::from_utf8(&code[start..end]) else {
+        // Without valid UTF-8 there is no sensible mapping, so treat
+        // the column as bytes.
+        return (start + point.col).min(end)
+    };
+
+    let mut units = 0;
+    for (idx, c) in line.char_indices() {
+        if units >= point.col {
+            return start + idx
+        }
+        units += c.len_utf16();
+    }
+    end
+}
+
+/// Map a [`Severity`] to an LSP `DiagnosticSeverity`.
+fn diagnostic_severity(severity: Severity) -> u8 {
+    match severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Info => 3,
+        Severity::Hint => 4,
+    }
+}
+
+/// Convert a lint match into an LSP diagnostic.
+fn diagnostic(r#match: &LintMatch, code: &[u8]) -> Value {
+    let (start, end) = r#match.range.utf16_points(code);
+    json!({
+        "range": {"start": position(start), "end": position(end)},
+        "severity": diagnostic_severity(r#match.severity),
+        "code": r#match.lint_name,
+        "source": "bpflint",
+        "message": r#match.message,
+    })
+}
+
+
+/// An open text document.
+#[derive(Debug)]
+struct Document {
+    /// The document's source code, along with its syntax tree.
+    source: bpflint::Document,
+    /// The directory of the configuration the document is linted with,
+    /// which is the key of the linter used, or `None` if the document
+    /// is not linted.
+    linter: Option<PathBuf>,
+    /// The lint matches found in the document.
+    matches: Vec<LintMatch>,
+}
+
+impl Document {
+    /// Retrieve matches overlapping the range `start` to `end`, which
+    /// are expressed with UTF-16 based columns, along with their
+    /// respective converted start and end points.
+    fn matches(
+        &self,
+        start: Point,
+        end: Point,
+    ) -> impl Iterator<Item = (&LintMatch, Point, Point)> {
+        self.matches.iter().filter_map(move |m| {
+            let (m_start, m_end) = m.range.utf16_points(self.source.code());
+            (m_start <= end && start <= m_end).then_some((m, m_start, m_end))
+        })
+    }
+}
+
+
+/// The state of the language server.
+struct Server<'w> {
+    writer: &'w mut dyn io::Write,
+    /// The configuration to use for all documents, if any.
+    config: Option<Config>,
+    /// Linter settings provided on the command line.
+    linter_args: LinterArgs,
+    /// Linters by the directory of the configuration they were created
+    /// from.
+    linters: HashMap<PathBuf, Linter>,
+    /// Open documents by URI.
+    documents: HashMap<String, Document>,
+    initialized: bool,
+    shutdown: bool,
+}
+
+impl Server<'_> {
+    fn respond(&mut self, id: &Value, result: Result<Value>) -> Result<()> {
+        let response = match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(err) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {"code": INTERNAL_ERROR, "message": format!("{err:#}")},
+            }),
+        };
+        send(self.writer, &response)
+    }
+
+    fn respond_error(&mut self, id: &Value, code: i64, message: &str) -> Result<()> {
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": code, "message": message},
+        });
+        send(self.writer, &response)
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> Result<()> {
+        let notification = json!({"jsonrpc": "2.0", "method": method, "params": params});
+        send(self.writer, &notification)
+    }
+
+    fn show_error(&mut self, err: &anyhow::Error) -> Result<()> {
+        self.notify(
+            "window/showMessage",
+            json!({"type": MESSAGE_ERROR, "message": format!("bpflint: {err:#}")}),
+        )
+    }
+
+    /// Find the linter for the document with the given URI, creating
+    /// it if necessary, and return its key.
+    ///
+    /// `None` is returned if the document should not be linted.
+    fn linter(&mut self, uri: &str) -> Result<Option<PathBuf>> {
+        let path = uri_to_path(uri);
+        let default_config = Config::default();
+        let config = match (&self.config, &path) {
+            (Some(config), _) => Cow::Borrowed(config),
+            (None, Some(path)) => Config::discover(path)
+                .with_context(|| {
+                    format!("failed to discover configuration for `{}`", path.display())
+                })?
+                .map(Cow::Owned)
+                .unwrap_or(Cow::Borrowed(&default_config)),
+            (None, None) => Cow::Borrowed(&default_config),
+        };
+
+        if path.as_deref().is_some_and(|path| config.is_ignored(path)) {
+            return Ok(None)
+        }
+
+        if let Entry::Vacant(entry) = self.linters.entry(config.dir.clone()) {
+            let (linter, _check_ext) =
+                create_linter(&config, &self.linter_args).context("failed to create linter")?;
+            let _linter = entry.insert(linter);
+        }
+        Ok(Some(config.dir.clone()))
+    }
+
+    /// Start tracking the document with the given URI.
+    fn open(&mut self, uri: &str, text: &str) -> Result<()> {
+        let linter = match self.linter(uri) {
+            Ok(linter) => linter,
+            Err(err) => {
+                let () = self.show_error(&err)?;
+                None
+            },
+        };
+        let document = Document {
+            source: bpflint::Document::new(text)?,
+            linter,
+            matches: Vec::new(),
+        };
+        let _document = self.documents.insert(uri.to_string(), document);
+        self.relint(uri)
+    }
+
+    /// Apply LSP content changes to the document with the given URI.
+    fn change(&mut self, uri: &str, changes: &[Value]) -> Result<()> {
+        let document = self
+            .documents
+            .get_mut(uri)
+            .with_context(|| format!("document `{uri}` is not open"))?;
+
+        for change in changes {
+            let text = change["text"]
+                .as_str()
+                .context("content change lacks text")?;
+            let code = document.source.code();
+            let bytes = match change.get("range") {
+                Some(range) => {
+                    let start = byte_offset(code, point(&range["start"])?);
+                    let end = byte_offset(code, point(&range["end"])?);
+                    start..end.max(start)
+                },
+                // Changes without range replace the entire document.
+                None => 0..code.len(),
+            };
+            let edit = TextEdit {
+                bytes,
+                text: text.as_bytes().to_vec(),
+            };
+            let () = document.source.edit(&edit)?;
+        }
+        self.relint(uri)
+    }
+
+    /// Lint the document with the given URI and publish diagnostics
+    /// for it.
+    fn relint(&mut self, uri: &str) -> Result<()> {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return Ok(())
+        };
+
+        let linter = document
+            .linter
+            .as_ref()
+            .and_then(|dir| self.linters.get(dir));
+        let result = match linter {
+            Some(linter) => document
+                .source
+                .lint(linter)
+                .map(<[_]>::to_vec)
+                .with_context(|| format!("failed to lint `{uri}`")),
+            None => Ok(Vec::new()),
+        };
+        document.matches = result.as_ref().cloned().unwrap_or_default();
+
+        let diagnostics = document
+            .matches
+            .iter()
+            .map(|m| diagnostic(m, document.source.code()))
+            .collect::<Vec<_>>();
+        if let Err(err) = result {
+            let () = self.show_error(&err)?;
+        }
+        self.notify(
+            "textDocument/publishDiagnostics",
+            json!({"uri": uri, "diagnostics": diagnostics}),
+        )
+    }
+
+    fn code_actions(&self, params: &Value) -> Result<Value> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .context("request lacks document URI")?;
+        let start = point(&params["range"]["start"])?;
+        let end = point(&params["range"]["end"])?;
+        let Some(document) = self.documents.get(uri) else {
+            return Ok(json!([]))
+        };
+
+        let mut actions = Vec::new();
+        let mut seen = Vec::new();
+        for (m, m_start, _m_end) in document.matches(start, end) {
+            let row = m.range.start_point.row;
+            if seen.contains(&(&m.lint_name, row)) {
+                continue
+            }
+            let () = seen.push((&m.lint_name, row));
+
+            let line = document
+                .source
+                .code()
+                .split(|b| *b == b'\n')
+                .nth(row)
+                .unwrap_or_default();
+            let indent = line
+                .iter()
+                .take_while(|b| b.is_ascii_whitespace())
+                .map(|b| char::from(*b))
+                .collect::<String>();
+            let insert = position(Point { row, col: 0 });
+            let action = json!({
+                "title": format!("Disable `{}` for this statement", m.lint_name),
+                "kind": "quickfix",
+                "diagnostics": [diagnostic(m, document.source.code())],
+                "edit": {
+                    "changes": {
+                        uri: [{
+                            "range": {"start": insert, "end": insert},
+                            "newText": format!("{indent}/* bpflint: disable={} */\n", m.lint_name),
+                        }],
+                    },
+                },
+            });
+            debug!(
+                row,
+                col = m_start.col,
+                lint = m.lint_name,
+                "offering code action"
+            );
+            let () = actions.push(action);
+        }
+        Ok(Value::Array(actions))
+    }
+
+    fn hover(&self, params: &Value) -> Result<Value> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .context("request lacks document URI")?;
+        let position = point(&params["position"])?;
+        let Some(document) = self.documents.get(uri) else {
+            return Ok(Value::Null)
+        };
+
+        let lints = document
+            .linter
+            .as_ref()
+            .and_then(|dir| self.linters.get(dir));
+        for (m, start, end) in document.matches(position, position) {
+            let Some(lint) =
+                lints.and_then(|linter| linter.lints().find(|lint| lint.name == m.lint_name))
+            else {
+                continue
+            };
+
+            let mut text = Vec::new();
+            let () = explain(lint, &mut text)?;
+            let text = String::from_utf8(text).context("lint explanation is not valid UTF-8")?;
+            return Ok(json!({
+                "contents": {"kind": "markdown", "value": text},
+                "range": {"start": self::position(start), "end": self::position(end)},
+            }))
+        }
+        Ok(Value::Null)
+    }
+
+    fn handle_request(&mut self, id: &Value, method: &str, params: &Value) -> Result<()> {
+        if !self.initialized && method != "initialize" {
+            return self.respond_error(id, SERVER_NOT_INITIALIZED, "server is not initialized")
+        }
+
+        let result = match method {
+            "initialize" => {
+                self.initialized = true;
+                Ok(json!({
+                    "capabilities": {
+                        "textDocumentSync": SYNC_INCREMENTAL,
+                        "codeActionProvider": true,
+                        "hoverProvider": true,
+                    },
+                    "serverInfo": {"name": "bpflinter", "version": env!("CARGO_PKG_VERSION")},
+                }))
+            },
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            },
+            "textDocument/codeAction" => self.code_actions(params),
+            "textDocument/hover" => self.hover(params),
+            _ => {
+                return self.respond_error(
+                    id,
+                    METHOD_NOT_FOUND,
+                    &format!("method `{method}` is not supported"),
+                )
+            },
+        };
+        self.respond(id, result)
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Result<()> {
+        let uri = || {
+            params["textDocument"]["uri"]
+                .as_str()
+                .context("notification lacks document URI")
+        };
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"]
+                    .as_str()
+                    .context("notification lacks document text")?;
+                self.open(uri()?, text)
+            },
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"]
+                    .as_array()
+                    .context("notification lacks content changes")?;
+                self.change(uri()?, changes)
+            },
+            "textDocument/didClose" => {
+                let uri = uri()?;
+                let _document = self.documents.remove(uri);
+                self.notify(
+                    "textDocument/publishDiagnostics",
+                    json!({"uri": uri, "diagnostics": []}),
+                )
+            },
+            _ => {
+                debug!(method, "ignoring notification");
+                Ok(())
+            },
+        }
+    }
+}
+
+
+/// Run a language server communicating via `reader` and `writer`.
+///
+/// Returns `true` if the client requested a shutdown before exiting
+/// and `false` otherwise.
+pub fn run(
+    config: Option<Config>,
+    linter_args: LinterArgs,
+    reader: &mut dyn BufRead,
+    writer: &mut dyn io::Write,
+) -> Result<bool> {
+    let mut server = Server {
+        writer,
+        config,
+        linter_args,
+        linters: HashMap::new(),
+        documents: HashMap::new(),
+        initialized: false,
+        shutdown: false,
+    };
+
+    while let Some(message) = receive(reader)? {
+        let method = message["method"].as_str();
+        let params = &message["params"];
+        match (message.get("id"), method) {
+            (_, Some("exit")) => return Ok(server.shutdown),
+            (Some(id), Some(method)) => server.handle_request(id, method, params)?,
+            (None, Some(method)) => {
+                if let Err(err) = server.handle_notification(method, params) {
+                    let () = server.show_error(&err)?;
+                }
+            },
+            // Responses to requests we never sent.
+            (_, None) => (),
+        }
+    }
+
+    bail!("client disconnected without exiting")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+
+    fn message(value: Value) -> String {
+        let body = value.to_string();
+        format!("Content-Length: {}\r\n\r\n{body}", body.len())
+    }
+
+    fn messages(output: &[u8]) -> Vec<Value> {
+        let mut reader = Cursor::new(output);
+        let mut messages = Vec::new();
+        while let Some(message) = receive(&mut reader).unwrap() {
+            let () = messages.push(message);
+        }
+        messages
+    }
+
+    /// Check that we can convert file URIs into paths.
+    #[test]
+    fn uri_conversion() {
+        assert_eq!(
+            uri_to_path("file:///home/user/foo%20bar.bpf.c"),
+            Some(PathBuf::from("/home/user/foo bar.bpf.c"))
+        );
+        assert_eq!(
+            uri_to_path("file://localhost/foo.bpf.c"),
+            Some(PathBuf::from("/foo.bpf.c"))
+        );
+        assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+        assert_eq!(uri_to_path("file:///foo%2"), None);
+    }
+
+    /// Check that we can convert UTF-16 based points into byte offsets.
+    #[test]
+    fn byte_offset_conversion() {
+        let code = "ab\nä😀c\n".as_bytes();
+        assert_eq!(byte_offset(code, Point { row: 0, col: 1 }), 1);
+        assert_eq!(byte_offset(code, Point { row: 0, col: 5 }), 2);
+        assert_eq!(byte_offset(code, Point { row: 1, col: 0 }), 3);
+        assert_eq!(byte_offset(code, Point { row: 1, col: 1 }), 5);
+        assert_eq!(byte_offset(code, Point { row: 1, col: 3 }), 9);
+        assert_eq!(byte_offset(code, Point { row: 1, col: 4 }), 10);
+        assert_eq!(byte_offset(code, Point { row: 2, col: 0 }), 11);
+        assert_eq!(byte_offset(code, Point { row: 5, col: 0 }), 11);
+    }
+
+    /// Check that the server publishes diagnostics and answers code
+    /// action and hover requests.
+    #[test]
+    fn server_session() {
+        let uri = "untitled:foo.bpf.c";
+        let text = "void f(void) {\n\t/* ä */ bpf_probe_read(a, b, c);\n}\n";
+        let input = [
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+            json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": {"textDocument": {"uri": uri, "languageId": "c", "version": 1, "text": text}},
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "textDocument/codeAction",
+                "params": {
+                    "textDocument": {"uri": uri},
+                    "range": {"start": {"line": 1, "character": 10}, "end": {"line": 1, "character": 10}},
+                    "context": {"diagnostics": []},
+                },
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "textDocument/hover",
+                "params": {"textDocument": {"uri": uri}, "position": {"line": 1, "character": 12}},
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 4,
+                "method": "textDocument/hover",
+                "params": {"textDocument": {"uri": uri}, "position": {"line": 0, "character": 0}},
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didChange",
+                "params": {
+                    "textDocument": {"uri": uri, "version": 2},
+                    "contentChanges": [
+                        {"range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 0}}, "text": "int y;\n"},
+                        {"range": {"start": {"line": 2, "character": 5}, "end": {"line": 2, "character": 8}}, "text": "ü */"},
+                    ],
+                },
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didChange",
+                "params": {"textDocument": {"uri": uri, "version": 3}, "contentChanges": [{"text": "int x;\n"}]},
+            }),
+            json!({"jsonrpc": "2.0", "id": 5, "method": "foo/bar"}),
+            json!({"jsonrpc": "2.0", "id": 6, "method": "shutdown"}),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+        ]
+        .into_iter()
+        .map(message)
+        .collect::<String>();
+
+        let mut output = Vec::new();
+        let shutdown = run(
+            None,
+            LinterArgs::default(),
+            &mut Cursor::new(input),
+            &mut output,
+        )
+        .unwrap();
+        assert!(shutdown);
+
+        let output = messages(&output);
+        assert_eq!(output.len(), 9, "{output:#?}");
+
+        assert_eq!(output[0]["id"], 1);
+        assert_eq!(output[0]["result"]["capabilities"]["textDocumentSync"], 2);
+
+        assert_eq!(output[1]["method"], "textDocument/publishDiagnostics");
+        let diagnostics = output[1]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        // The 'ä' takes up two bytes in UTF-8, but a single code unit
+        // in UTF-16.
+        assert_eq!(
+            diagnostics[0]["range"],
+            json!({"start": {"line": 1, "character": 9}, "end": {"line": 1, "character": 23}})
+        );
+        assert_eq!(diagnostics[0]["severity"], 2);
+        assert_eq!(diagnostics[0]["code"], "probe-read");
+
+        assert_eq!(output[2]["id"], 2);
+        let actions = output[2]["result"].as_array().unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            actions[0]["edit"]["changes"][uri],
+            json!([{
+                "range": {"start": {"line": 1, "character": 0}, "end": {"line": 1, "character": 0}},
+                "newText": "\t/* bpflint: disable=probe-read */\n",
+            }])
+        );
+
+        assert_eq!(output[3]["id"], 3);
+        let hover = output[3]["result"]["contents"]["value"].as_str().unwrap();
+        assert!(hover.starts_with("probe-read: "), "{hover}");
+
+        assert_eq!(output[4]["id"], 4);
+        assert_eq!(output[4]["result"], Value::Null);
+
+        // After the incremental change, the match moved down by one line
+        // and the comment preceding it grew by one UTF-16 code unit.
+        let diagnostics = output[5]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0]["range"],
+            json!({"start": {"line": 2, "character": 10}, "end": {"line": 2, "character": 24}})
+        );
+
+        assert_eq!(output[6]["params"]["diagnostics"], json!([]));
+        assert_eq!(output[7]["id"], 5);
+        assert_eq!(output[7]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(output[8]["id"], 6);
+        assert_eq!(output[8]["result"], Value::Null);
+    }
+}
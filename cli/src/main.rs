@@ -1,30 +1,68 @@
 //! A linter for BPF C code.
 
 mod args;
+mod baseline;
+mod diff;
+mod lsp;
+mod stats;
 
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::env::current_dir;
 use std::env::var_os;
 use std::fs::read;
 use std::io;
+use std::io::IsTerminal as _;
 use std::io::Write as _;
 use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
 use anyhow::Context as _;
 use anyhow::Result;
+use anyhow::bail;
 
 use clap::Parser as _;
 
 use tracing::Level;
+use tracing::info;
 use tracing::subscriber::set_global_default as set_global_subscriber;
 use tracing_subscriber::FmtSubscriber;
 use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::fmt::time::ChronoLocal;
 
+use bpflint::Config;
+use bpflint::Lint;
 use bpflint::LintMatch;
+use bpflint::LintMeta;
+use bpflint::Linter;
 use bpflint::Point;
 use bpflint::Range;
+use bpflint::ReportOpts;
+use bpflint::Severity;
 use bpflint::builtin_lints;
-use bpflint::lint;
-use bpflint::report_terminal;
+use bpflint::report_github;
+use bpflint::report_json;
+use bpflint::report_sarif;
+use bpflint::report_short;
+use bpflint::report_terminal_opts;
+
+use crate::baseline::Baseline;
+use crate::diff::Diff;
+use crate::stats::Stats;
+
+
+/// The name of the pseudo lint flagging files without `.bpf.c`
+/// extension.
+const BOGUS_FILE_EXTENSION: &str = "bogus-file-extension";
+/// The name of the lint flagging code that could not be parsed.
+const SYNTAX_ERROR: &str = "syntax-error";
+/// The exit status used when denied lint matches were reported.
+const EXIT_DENIED: u8 = 1;
+/// The exit status used when an error occurred.
+const EXIT_ERROR: u8 = 2;
 
 
 fn has_bpf_c_ext(path: &Path) -> bool {
@@ -40,12 +78,197 @@ fn has_bpf_c_ext(path: &Path) -> bool {
     false
 }
 
-fn main() -> Result<()> {
+/// Remove the pseudo lint `name` from the lint selection of `config`,
+/// returning whether it is selected.
+fn take_pseudo_lint(config: &mut Config, name: &str) -> bool {
+    let selected = match &mut config.only {
+        Some(only) => {
+            let selected = only.iter().any(|lint| lint == name);
+            let () = only.retain(|lint| lint != name);
+            selected
+        },
+        None => true,
+    };
+    let enabled = config.enable.iter().any(|lint| lint == name);
+    let () = config.enable.retain(|lint| lint != name);
+    let disabled = config.disable.iter().any(|lint| lint == name);
+    let () = config.disable.retain(|lint| lint != name);
+    (selected && !disabled) || enabled
+}
+
+/// Create a [`Linter`] based on the provided configuration, with
+/// command line options taking precedence.
+///
+/// Also returned is whether the `bogus-file-extension` pseudo lint is
+/// selected.
+fn create_linter(config: &Config, args: &args::LinterArgs) -> Result<(Linter, bool)> {
+    let args::LinterArgs {
+        lint_files,
+        lint_dirs,
+        severities,
+        only,
+        enable,
+        disable,
+    } = args;
+
+    let mut config = config.clone();
+    let () = config.lint_files.extend(lint_files.iter().cloned());
+    let () = config.lint_dirs.extend(lint_dirs.iter().cloned());
+    let () = config.severities.extend(severities.iter().cloned());
+    if !only.is_empty() {
+        config.only = Some(only.clone());
+    }
+    let () = config.enable.extend(enable.iter().cloned());
+    let () = config.disable.extend(disable.iter().cloned());
+    let check_ext = take_pseudo_lint(&mut config, BOGUS_FILE_EXTENSION);
+    let linter = config.linter()?;
+    Ok((linter, check_ext))
+}
+
+/// Print a detailed explanation of the given lint.
+fn explain(lint: &LintMeta, writer: &mut dyn io::Write) -> Result<()> {
+    let LintMeta {
+        name,
+        default_severity,
+        summary,
+        rationale,
+        references,
+        examples,
+        enabled_by_default,
+        ..
+    } = lint;
+
+    writeln!(writer, "{name}: {summary}")?;
+    writeln!(writer)?;
+    writeln!(writer, "Default severity: {default_severity}")?;
+    if !enabled_by_default {
+        writeln!(
+            writer,
+            "Disabled by default; enable it via `--enable {name}` or the `lints.enable` configuration key"
+        )?;
+    }
+    writeln!(writer)?;
+    writeln!(writer, "{rationale}")?;
+
+    for example in examples {
+        writeln!(writer)?;
+        writeln!(writer, "Code like the following is flagged by the lint:")?;
+        writeln!(writer)?;
+        for line in example.bad.lines() {
+            writeln!(writer, "    {line}")?;
+        }
+        writeln!(writer)?;
+        writeln!(writer, "Consider writing it as follows instead:")?;
+        writeln!(writer)?;
+        for line in example.good.lines() {
+            writeln!(writer, "    {line}")?;
+        }
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "If the lint does not apply, it can be disabled for the following statement or block with a comment:"
+    )?;
+    writeln!(writer)?;
+    writeln!(writer, "    /* bpflint: disable={name} */")?;
+
+    if !references.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "References:")?;
+        for reference in references {
+            writeln!(writer, "- {reference}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Check whether `match` is denied by the provided policies, meaning
+/// that it should result in a failure exit status.
+fn is_denied(r#match: &LintMatch, deny: &[args::Deny]) -> bool {
+    deny.iter().any(|deny| match deny {
+        args::Deny::None => false,
+        args::Deny::Severity(severity) => r#match.severity >= *severity,
+        args::Deny::Lint(name) => r#match.lint_name == *name,
+    })
+}
+
+/// Complete the user provided deny policies with the default one of
+/// denying matches with 'error' severity, unless a severity based
+/// policy (or `none`) was provided explicitly.
+fn deny_policies(mut deny: Vec<args::Deny>) -> Vec<args::Deny> {
+    let explicit = deny
+        .iter()
+        .any(|deny| matches!(deny, args::Deny::None | args::Deny::Severity(..)));
+    if !explicit {
+        let () = deny.push(args::Deny::Severity(Severity::Error));
+    }
+    deny
+}
+
+/// Enable the `syntax-error` lint and deny its matches.
+fn deny_syntax_errors(linter_args: &mut args::LinterArgs, deny: &mut Vec<args::Deny>) {
+    let () = linter_args.enable.push(SYNTAX_ERROR.to_string());
+    let () = deny.push(args::Deny::Lint(SYNTAX_ERROR.to_string()));
+}
+
+/// Gather the names of all lints that may be reported, i.e., the
+/// built-in ones as well as custom lints loaded from `lint_files` and
+/// `lint_dirs`.
+fn known_lints(lint_files: &[PathBuf], lint_dirs: &[PathBuf]) -> Result<Vec<String>> {
+    let mut known = builtin_lints()
+        .map(|lint| lint.name)
+        .chain([BOGUS_FILE_EXTENSION.to_string()])
+        .collect::<Vec<_>>();
+    for path in lint_files {
+        let lint = Lint::from_file(path)
+            .with_context(|| format!("failed to load lint `{}`", path.display()))?;
+        let () = known.push(lint.meta().name.clone());
+    }
+    for dir in lint_dirs {
+        for lint in Lint::load_dir(dir)? {
+            let () = known.push(lint.meta().name.clone());
+        }
+    }
+    Ok(known)
+}
+
+/// Check that all lints referenced by `deny` policies are among the
+/// `known` ones.
+fn validate_deny(deny: &[args::Deny], known: &[String]) -> Result<()> {
+    for deny in deny {
+        if let args::Deny::Lint(name) = deny {
+            if !known.contains(name) {
+                bail!("lint `{name}` is unknown; use --print-lints to list available lints")
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run() -> Result<ExitCode> {
     let args::Args {
+        command,
         srcs,
+        config,
+        linter: mut linter_args,
+        format,
+        color,
+        context,
+        deny,
+        deny_syntax_errors: deny_syntax_errs,
+        stats: stats_only,
+        baseline,
+        write_baseline,
+        diff,
+        diff_root,
         print_lints,
         verbosity,
     } = args::Args::parse();
+    let mut deny = deny_policies(deny);
+    if deny_syntax_errs {
+        let () = deny_syntax_errors(&mut linter_args, &mut deny);
+    }
 
     let level = match verbosity {
         0 => Level::WARN,
@@ -54,7 +277,10 @@ fn main() -> Result<()> {
         _ => Level::TRACE,
     };
 
+    // Log to stderr, so as to not interfere with reports or the
+    // language server protocol on stdout.
     let builder = FmtSubscriber::builder()
+        .with_writer(io::stderr)
         .with_timer(ChronoLocal::new("%Y-%m-%dT%H:%M:%S%.3f%:z".to_string()));
 
     if let Some(directive) = var_os(EnvFilter::DEFAULT_ENV) {
@@ -72,11 +298,26 @@ fn main() -> Result<()> {
     };
 
     let stdout = io::stdout();
+    let color = match color {
+        args::ColorChoice::Always => true,
+        args::ColorChoice::Never => false,
+        args::ColorChoice::Auto => {
+            // See https://no-color.org
+            let no_color = var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            !no_color && stdout.is_terminal()
+        },
+    };
+    let report_opts = ReportOpts {
+        color,
+        context,
+        ..Default::default()
+    };
     let mut stdout = stdout.lock();
 
     let m_ext_is_c = LintMatch {
-        lint_name: "bogus-file-extension".to_string(),
+        lint_name: BOGUS_FILE_EXTENSION.to_string(),
         message: "by convention BPF C code should use the file extension '.bpf.c'".to_string(),
+        severity: Severity::Warning,
         range: Range {
             bytes: 0..0,
             start_point: Point { row: 0, col: 0 },
@@ -84,27 +325,196 @@ fn main() -> Result<()> {
         },
     };
 
-    if print_lints {
-        for lint in builtin_lints() {
-            writeln!(&mut stdout, "{}", lint.name)?;
+    if let Some(command) = command {
+        match command {
+            args::Command::Explain(args::Explain { lint }) => {
+                let lint = builtin_lints()
+                    .find(|meta| meta.name == lint)
+                    .with_context(|| {
+                        format!(
+                            "lint `{lint}` is unknown; use --print-lints to list available lints"
+                        )
+                    })?;
+                let () = explain(&lint, &mut stdout)?;
+            },
+            args::Command::Lsp(args::Lsp { config, linter }) => {
+                let config = config.map(|path| Config::from_file(&path)).transpose()?;
+                let shutdown = lsp::run(config, linter, &mut io::stdin().lock(), &mut stdout)?;
+                // The protocol mandates a failure exit status if the
+                // client did not request a shutdown before exiting.
+                if !shutdown {
+                    return Ok(ExitCode::FAILURE)
+                }
+            },
+        }
+    } else if print_lints {
+        let lints = builtin_lints().collect::<Vec<_>>();
+        let width = lints.iter().map(|lint| lint.name.len()).max().unwrap_or(0);
+        for lint in lints {
+            let suffix = if lint.enabled_by_default {
+                ""
+            } else {
+                " (disabled by default)"
+            };
+            writeln!(
+                &mut stdout,
+                "{:width$}  {}{suffix}",
+                lint.name, lint.summary
+            )?;
         }
     } else {
+        let config = config.map(|path| Config::from_file(&path)).transpose()?;
+        let default_config = Config::default();
+
+        // Validate deny policies upfront, so that typos are caught
+        // irrespective of the files being linted. Custom lints from
+        // discovered configuration files are not known at this point,
+        // but the ones specified explicitly are.
+        if deny.iter().any(|deny| matches!(deny, args::Deny::Lint(..))) {
+            let config = config.as_ref().unwrap_or(&default_config);
+            let lint_files = [config.lint_files.as_slice(), &linter_args.lint_files].concat();
+            let lint_dirs = [config.lint_dirs.as_slice(), &linter_args.lint_dirs].concat();
+            let known = known_lints(&lint_files, &lint_dirs)?;
+            let () = validate_deny(&deny, &known)?;
+        }
+        // Linters are cached by the directory of the configuration they
+        // were created from.
+        let mut linters = HashMap::<PathBuf, (Linter, bool)>::new();
+        // SARIF output covers all files at once and so matches are
+        // collected for reporting at the very end.
+        let mut sarif = Vec::<(PathBuf, Vec<u8>, Vec<LintMatch>)>::new();
+        let mut baseline = baseline.map(|path| Baseline::load(&path)).transpose()?;
+        let diff = diff
+            .map(|path| {
+                let root = match diff_root {
+                    Some(root) => root,
+                    None => current_dir().context("failed to retrieve working directory")?,
+                };
+                Diff::load(&path, &root)
+            })
+            .transpose()?;
+        let mut new_baseline = write_baseline.as_ref().map(|_| Baseline::default());
+        let mut stats = Stats::default();
+        let mut denied = false;
+
         for src_path in srcs.into_iter().flatten() {
+            let config = match &config {
+                Some(config) => Cow::Borrowed(config),
+                None => Config::discover(&src_path)
+                    .with_context(|| {
+                        format!(
+                            "failed to discover configuration for `{}`",
+                            src_path.display()
+                        )
+                    })?
+                    .map(Cow::Owned)
+                    .unwrap_or(Cow::Borrowed(&default_config)),
+            };
+
+            if config.is_ignored(&src_path) {
+                info!("skipping ignored file `{}`", src_path.display());
+                continue
+            }
+
+            let (linter, check_ext) = match linters.entry(config.dir.clone()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let linter =
+                        create_linter(&config, &linter_args).context("failed to create linter")?;
+                    entry.insert(linter)
+                },
+            };
+
             let code = read(&src_path)
                 .with_context(|| format!("failed to read `{}`", src_path.display()))?;
 
-            if !has_bpf_c_ext(&src_path) {
-                let () = report_terminal(&m_ext_is_c, &code, &src_path, &mut stdout)?;
+            let mut matches = linter
+                .lint(&code)
+                .with_context(|| format!("failed to lint `{}`", src_path.display()))?;
+            if *check_ext && !has_bpf_c_ext(&src_path) {
+                let () = matches.insert(0, m_ext_is_c.clone());
+            }
+
+            if let Some(new_baseline) = &mut new_baseline {
+                for m in &matches {
+                    let () = new_baseline.add(&src_path, m, &code);
+                }
+                continue
+            }
+
+            if let Some(diff) = &diff {
+                let () = matches.retain(|m| diff.contains(&src_path, m));
+            }
+
+            if let Some(baseline) = &mut baseline {
+                let () = matches.retain(|m| !baseline.remove(&src_path, m, &code));
+            }
+
+            let () = stats.add_file(&matches);
+            denied |= matches.iter().any(|m| is_denied(m, &deny));
+
+            if stats_only {
+                continue
+            }
+
+            if format == args::Format::Sarif {
+                let () = sarif.push((src_path, code, matches));
+                continue
             }
 
-            let matches =
-                lint(&code).with_context(|| format!("failed to lint `{}`", src_path.display()))?;
             for m in matches {
-                let () = report_terminal(&m, &code, &src_path, &mut stdout)?;
+                let () = match format {
+                    args::Format::Terminal => {
+                        report_terminal_opts(&m, &code, &src_path, &report_opts, &mut stdout)
+                    },
+                    args::Format::Short => report_short(&m, &src_path, &mut stdout),
+                    args::Format::Json => report_json(&m, &src_path, &mut stdout),
+                    args::Format::Github => report_github(&m, &src_path, &mut stdout),
+                    args::Format::Sarif => unreachable!("SARIF matches are collected separately"),
+                }?;
             }
         }
+
+        if let (Some(path), Some(new_baseline)) = (write_baseline, new_baseline) {
+            let () = new_baseline.save(&path)?;
+            return Ok(ExitCode::SUCCESS)
+        }
+
+        if stats_only {
+            let () = match format {
+                args::Format::Json => stats.write_json(&mut stdout),
+                _ => stats.write_table(&mut stdout),
+            }?;
+        } else if format == args::Format::Terminal {
+            writeln!(&mut stdout)?;
+            let () = stats.write_table(&mut stdout)?;
+        } else if format == args::Format::Sarif {
+            let lints = linters
+                .values()
+                .flat_map(|(linter, _check_ext)| linter.lints())
+                .map(|lint| (&lint.name, lint))
+                .collect::<BTreeMap<_, _>>()
+                .into_values()
+                .cloned()
+                .collect::<Vec<_>>();
+            let matches = sarif.iter().flat_map(|(path, code, matches)| {
+                matches.iter().map(|m| (path.as_path(), code.as_slice(), m))
+            });
+            let () = report_sarif(&lints, matches, &mut stdout)?;
+        }
+
+        if denied {
+            return Ok(ExitCode::from(EXIT_DENIED))
+        }
     }
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    run().unwrap_or_else(|err| {
+        eprintln!("Error: {err:?}");
+        ExitCode::from(EXIT_ERROR)
+    })
 }
 
 
@@ -112,6 +522,12 @@ fn main() -> Result<()> {
 mod tests {
     use super::*;
 
+    use std::fs::write;
+
+    use tempfile::tempdir;
+
+    use bpflint::LintExample;
+
 
     /// Test that [`has_bpf_c_ext`] works correctly for various
     /// paths/extensions.
@@ -125,4 +541,173 @@ mod tests {
         assert!(!has_bpf_c_ext(Path::new("file.bpf.h")));
         assert!(!has_bpf_c_ext(Path::new("filebpfc")));
     }
+
+    /// Check that we explain lints as expected.
+    #[test]
+    fn lint_explanation() {
+        let lint = LintMeta {
+            name: "foo".to_string(),
+            default_severity: Severity::Error,
+            summary: "Usage of foo()".to_string(),
+            rationale: "foo() is bad.\nPlease don't use it.".to_string(),
+            references: vec!["https://example.com/foo".to_string()],
+            examples: vec![LintExample {
+                bad: "foo();".to_string(),
+                good: "{\n    bar();\n}".to_string(),
+            }],
+            enabled_by_default: true,
+            _non_exhaustive: (),
+        };
+
+        let mut explanation = Vec::new();
+        let () = explain(&lint, &mut explanation).unwrap();
+        let explanation = String::from_utf8(explanation).unwrap();
+        let expected = r#"foo: Usage of foo()
+
+Default severity: error
+
+foo() is bad.
+Please don't use it.
+
+Code like the following is flagged by the lint:
+
+    foo();
+
+Consider writing it as follows instead:
+
+    {
+        bar();
+    }
+
+If the lint does not apply, it can be disabled for the following statement or block with a comment:
+
+    /* bpflint: disable=foo */
+
+References:
+- https://example.com/foo
+"#;
+        assert_eq!(explanation, expected);
+    }
+
+    /// Check that deny policies are evaluated correctly.
+    #[test]
+    fn deny_evaluation() {
+        let m = |severity| LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "bpf_probe_read() is deprecated".to_string(),
+            severity,
+            range: Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            },
+        };
+
+        assert!(!is_denied(&m(Severity::Error), &[]));
+
+        let deny = deny_policies(Vec::new());
+        assert!(!is_denied(&m(Severity::Warning), &deny));
+        assert!(is_denied(&m(Severity::Error), &deny));
+
+        let deny = deny_policies(vec![args::Deny::None]);
+        assert!(!is_denied(&m(Severity::Error), &deny));
+
+        let deny = deny_policies(vec![args::Deny::Lint("foo".to_string())]);
+        assert!(is_denied(&m(Severity::Error), &deny));
+
+        let deny = [args::Deny::Severity(Severity::Warning)];
+        assert!(is_denied(&m(Severity::Warning), &deny));
+        assert!(!is_denied(&m(Severity::Info), &deny));
+
+        let deny = [args::Deny::Lint("probe-read".to_string())];
+        assert!(is_denied(&m(Severity::Hint), &deny));
+        let deny = [args::Deny::Lint("foo".to_string())];
+        assert!(!is_denied(&m(Severity::Hint), &deny));
+    }
+
+    /// Check that `--deny-syntax-errors` causes code that could not be
+    /// parsed to be denied.
+    #[test]
+    fn syntax_error_denial() {
+        let code = b"int x = ;";
+        let mut linter_args = args::LinterArgs::default();
+        let mut deny = deny_policies(Vec::new());
+
+        let (linter, _check_ext) = create_linter(&Config::default(), &linter_args).unwrap();
+        let matches = linter.lint(code).unwrap();
+        assert!(!matches.iter().any(|m| is_denied(m, &deny)));
+
+        let () = deny_syntax_errors(&mut linter_args, &mut deny);
+        let (linter, _check_ext) = create_linter(&Config::default(), &linter_args).unwrap();
+        let matches = linter.lint(code).unwrap();
+        assert!(
+            matches
+                .iter()
+                .any(|m| m.lint_name == SYNTAX_ERROR && is_denied(m, &deny))
+        );
+    }
+
+    /// Check that the `bogus-file-extension` pseudo lint is subject to
+    /// lint selection.
+    #[test]
+    fn bogus_file_extension_selection() {
+        let config = Config::default();
+        let (_linter, check_ext) = create_linter(&config, &args::LinterArgs::default()).unwrap();
+        assert!(check_ext);
+
+        let args = args::LinterArgs {
+            only: vec!["probe-read".to_string()],
+            ..Default::default()
+        };
+        let (linter, check_ext) = create_linter(&config, &args).unwrap();
+        assert!(!check_ext);
+        assert_eq!(linter.lints().len(), 1);
+
+        let args = args::LinterArgs {
+            only: vec![BOGUS_FILE_EXTENSION.to_string()],
+            ..Default::default()
+        };
+        let (linter, check_ext) = create_linter(&config, &args).unwrap();
+        assert!(check_ext);
+        assert_eq!(linter.lints().len(), 0);
+
+        let args = args::LinterArgs {
+            disable: vec![BOGUS_FILE_EXTENSION.to_string()],
+            ..Default::default()
+        };
+        let (_linter, check_ext) = create_linter(&config, &args).unwrap();
+        assert!(!check_ext);
+
+        let args = args::LinterArgs {
+            only: vec!["probe-read".to_string()],
+            enable: vec![BOGUS_FILE_EXTENSION.to_string()],
+            ..Default::default()
+        };
+        let (_linter, check_ext) = create_linter(&config, &args).unwrap();
+        assert!(check_ext);
+    }
+
+    /// Check that we reject deny policies referring to unknown lints.
+    #[test]
+    fn deny_validation() {
+        let dir = tempdir().unwrap();
+        let lint = r#"((identifier) @id (#eq? @id "foo") (#set! "message" "foo"))"#;
+        let () = write(dir.path().join("foo.scm"), lint).unwrap();
+
+        let known = known_lints(&[], &[dir.path().to_path_buf()]).unwrap();
+        let deny = [
+            args::Deny::Severity(Severity::Info),
+            args::Deny::Lint("probe-read".to_string()),
+            args::Deny::Lint("foo".to_string()),
+            args::Deny::Lint(BOGUS_FILE_EXTENSION.to_string()),
+        ];
+        let () = validate_deny(&deny, &known).unwrap();
+
+        let deny = [args::Deny::Lint("prob-read".to_string())];
+        let err = validate_deny(&deny, &known).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "lint `prob-read` is unknown; use --print-lints to list available lints"
+        );
+    }
 }
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use anyhow::Result;
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use bpflint::LintMatch;
+use bpflint::Severity;
+
+
+/// All severities, in ascending order.
+const SEVERITIES: [Severity; 4] = [
+    Severity::Hint,
+    Severity::Info,
+    Severity::Warning,
+    Severity::Error,
+];
+
+
+/// Match counts, indexed by severity.
+type Counts = [usize; SEVERITIES.len()];
+
+fn severity_idx(severity: Severity) -> usize {
+    SEVERITIES
+        .iter()
+        .position(|other| *other == severity)
+        .unwrap_or_default()
+}
+
+fn counts_json(counts: &Counts) -> Value {
+    SEVERITIES
+        .iter()
+        .zip(counts)
+        .map(|(severity, count)| (severity.to_string(), Value::from(*count)))
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+
+/// Aggregate statistics about the lint matches of a run.
+#[derive(Debug, Default)]
+pub struct Stats {
+    /// The number of files checked.
+    files: usize,
+    /// The number of files with at least one match.
+    files_with_matches: usize,
+    /// Match counts by lint name.
+    lints: BTreeMap<String, Counts>,
+}
+
+impl Stats {
+    /// Account for a checked file with the provided matches.
+    pub fn add_file(&mut self, matches: &[LintMatch]) {
+        self.files += 1;
+        if !matches.is_empty() {
+            self.files_with_matches += 1;
+        }
+
+        for m in matches {
+            let counts = self.lints.entry(m.lint_name.clone()).or_default();
+            counts[severity_idx(m.severity)] += 1;
+        }
+    }
+
+    fn total(&self) -> Counts {
+        self.lints
+            .values()
+            .fold(Counts::default(), |mut total, counts| {
+                let () = total
+                    .iter_mut()
+                    .zip(counts)
+                    .for_each(|(total, count)| *total += count);
+                total
+            })
+    }
+
+    /// Print the statistics in the form of a table.
+    pub fn write_table(&self, writer: &mut dyn io::Write) -> Result<()> {
+        writeln!(
+            writer,
+            "Checked {} file(s), {} with findings",
+            self.files, self.files_with_matches
+        )?;
+        if self.lints.is_empty() {
+            return Ok(())
+        }
+
+        let total = self.total();
+        let rows = self
+            .lints
+            .iter()
+            .map(|(name, counts)| (name.as_str(), counts))
+            .chain([("total", &total)]);
+        let width = self
+            .lints
+            .keys()
+            .map(String::len)
+            .chain(["lint".len(), "total".len()])
+            .max()
+            .unwrap_or_default();
+
+        writeln!(writer)?;
+        write!(writer, "{:width$}", "lint")?;
+        for severity in SEVERITIES {
+            write!(writer, "  {severity}")?;
+        }
+        writeln!(writer)?;
+
+        for (name, counts) in rows {
+            write!(writer, "{name:width$}")?;
+            for (severity, count) in SEVERITIES.iter().zip(counts) {
+                let width = severity.to_string().len();
+                write!(writer, "  {count:>width$}")?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Print the statistics as a JSON object.
+    pub fn write_json(&self, writer: &mut dyn io::Write) -> Result<()> {
+        let lints = self
+            .lints
+            .iter()
+            .map(|(name, counts)| (name.clone(), counts_json(counts)))
+            .collect::<Map<_, _>>();
+        let json = json!({
+            "files": self.files,
+            "files_with_findings": self.files_with_matches,
+            "lints": lints,
+            "total": counts_json(&self.total()),
+        });
+        writeln!(writer, "{json}")?;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bpflint::Point;
+    use bpflint::Range;
+
+
+    fn stats() -> Stats {
+        let m = |lint_name: &str, severity| LintMatch {
+            lint_name: lint_name.to_string(),
+            message: String::new(),
+            severity,
+            range: Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            },
+        };
+
+        let mut stats = Stats::default();
+        let () = stats.add_file(&[
+            m("probe-read", Severity::Warning),
+            m("probe-read", Severity::Warning),
+            m("untyped-map-member", Severity::Error),
+        ]);
+        let () = stats.add_file(&[]);
+        let () = stats.add_file(&[m("bogus-file-extension", Severity::Warning)]);
+        stats
+    }
+
+    /// Check that we print statistics in tabular form as expected.
+    #[test]
+    fn table_printing() {
+        let mut output = Vec::new();
+        let () = stats().write_table(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let expected = "\
+Checked 3 file(s), 2 with findings
+
+lint                  hint  info  warning  error
+bogus-file-extension     0     0        1      0
+probe-read               0     0        2      0
+untyped-map-member       0     0        0      1
+total                    0     0        3      1
+";
+        assert_eq!(output, expected);
+
+        let mut output = Vec::new();
+        let () = Stats::default().write_table(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, "Checked 0 file(s), 0 with findings\n");
+    }
+
+    /// Check that we print statistics as JSON as expected.
+    #[test]
+    fn json_printing() {
+        let mut output = Vec::new();
+        let () = stats().write_json(&mut output).unwrap();
+        let output = serde_json::from_slice::<Value>(&output).unwrap();
+        let expected = json!({
+            "files": 3,
+            "files_with_findings": 2,
+            "lints": {
+                "bogus-file-extension": {"hint": 0, "info": 0, "warning": 1, "error": 0},
+                "probe-read": {"hint": 0, "info": 0, "warning": 2, "error": 0},
+                "untyped-map-member": {"hint": 0, "info": 0, "warning": 0, "error": 1},
+            },
+            "total": {"hint": 0, "info": 0, "warning": 3, "error": 1},
+        });
+        assert_eq!(output, expected);
+    }
+}
@@ -7,3 +7,13 @@
                )
     (#set! "message" "bpf_probe_read() is deprecated and replaced by bpf_probe_user() and bpf_probe_kernel(); refer to bpf-helpers(7)")
 )
+
+(call_expression
+    function: (identifier) @function (#eq? @function "bpf_probe_read_str")
+    arguments: (argument_list
+                  (expression)
+                  (expression)
+                  (expression)
+               )
+    (#set! "message" "bpf_probe_read_str() is deprecated and replaced by bpf_probe_read_user_str() and bpf_probe_read_kernel_str(); refer to bpf-helpers(7)")
+)
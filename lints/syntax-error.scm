@@ -0,0 +1,11 @@
+(
+  (ERROR) @report
+  (#set! "message" "syntax error; the affected code may not be covered by lints")
+  (#set! "severity" "info")
+)
+
+(
+  (MISSING) @report
+  (#set! "message" "syntax error due to missing code; the surrounding code may not be covered by lints")
+  (#set! "severity" "info")
+)
@@ -0,0 +1,552 @@
+use std::fs::canonicalize;
+use std::fs::read_to_string;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr as _;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::anyhow;
+
+use toml::Table;
+use toml::Value;
+
+use crate::Lint;
+use crate::Linter;
+use crate::Severity;
+
+
+/// Retrieve the array of strings stored in `value`.
+fn strings(value: &Value, key: &str) -> Result<Vec<String>> {
+    value
+        .as_array()
+        .with_context(|| format!("`{key}` is not an array"))?
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .with_context(|| format!("`{key}` contains a non-string value"))
+        })
+        .collect()
+}
+
+/// Check whether the single path component `s` matches `pattern`, with
+/// `*` matching any sequence of characters and `?` matching any single
+/// character.
+fn matches_component(pattern: &[char], s: &[char]) -> bool {
+    match (pattern.split_first(), s.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => {
+            matches_component(rest, s) || (!s.is_empty() && matches_component(pattern, &s[1..]))
+        },
+        (Some(('?', rest)), Some((_, s_rest))) => matches_component(rest, s_rest),
+        (Some((c, rest)), Some((s_c, s_rest))) => c == s_c && matches_component(rest, s_rest),
+        _ => false,
+    }
+}
+
+/// Check whether the path `components` match the pattern `components`,
+/// with `**` matching any number of path components.
+fn matches_components(pattern: &[Vec<char>], components: &[Vec<char>]) -> bool {
+    match (pattern.split_first(), components.split_first()) {
+        (None, None) => true,
+        (Some((first, rest)), _) if first.iter().eq(['*', '*'].iter()) => {
+            matches_components(rest, components)
+                || (!components.is_empty() && matches_components(pattern, &components[1..]))
+        },
+        (Some((first, rest)), Some((component, c_rest))) => {
+            matches_component(first, component) && matches_components(rest, c_rest)
+        },
+        _ => false,
+    }
+}
+
+/// Check whether the relative `path` matches the glob `pattern`.
+fn matches_glob(pattern: &str, path: &Path) -> bool {
+    let pattern = pattern
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .map(|component| component.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let components = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(component) => Some(component.to_string_lossy().chars().collect()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    matches_components(&pattern, &components)
+}
+
+
+/// Project level configuration of the linter, as typically read from a
+/// `bpflint.toml` file.
+///
+/// A configuration file has the following format, with all keys being
+/// optional:
+/// ```toml
+/// # Glob patterns of paths to not lint at all, relative to the
+/// # directory containing the configuration file. `*` and `?` match
+/// # within a path component, while `**` matches any number of
+/// # components. A pattern matching a directory excludes all its
+/// # contents.
+/// ignore = ["vendor", "**/*.skel.bpf.c"]
+///
+/// [lints]
+/// # If present, only the listed lints are run.
+/// only = ["probe-read"]
+/// # Lints that should be run, even if excluded by `only` or `disable`
+/// # or not enabled by default.
+/// enable = ["syntax-error"]
+/// # Lints that should not be run.
+/// disable = ["unstable-attach-point"]
+/// # Directories containing custom lints, as `*.scm` files.
+/// dirs = ["tools/lints"]
+/// # Individual custom lint files.
+/// files = ["tools/no-tail-calls.scm"]
+///
+/// # Overwrites of the severity with which lints are reported.
+/// [lints.severity]
+/// probe-read = "error"
+/// ```
+///
+/// Relative paths are interpreted relative to the directory containing
+/// the configuration file.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// The directory relative to which `ignore` patterns are
+    /// interpreted.
+    pub dir: PathBuf,
+    /// Glob patterns of paths to not lint.
+    pub ignore: Vec<String>,
+    /// The names of the lints to exclusively run, if any.
+    pub only: Option<Vec<String>>,
+    /// The names of lints to run, even if excluded by `only` or
+    /// `disable`.
+    pub enable: Vec<String>,
+    /// The names of lints to not run.
+    pub disable: Vec<String>,
+    /// Directories from which to load custom lints.
+    pub lint_dirs: Vec<PathBuf>,
+    /// Files from which to load custom lints.
+    pub lint_files: Vec<PathBuf>,
+    /// Overwrites of lint severities, applied in order.
+    pub severities: Vec<(String, Severity)>,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Config {
+    /// The name of a file containing project level configuration.
+    pub const FILE_NAME: &str = "bpflint.toml";
+
+    /// Parse a configuration from its TOML representation.
+    ///
+    /// - `toml` is the configuration in TOML format
+    /// - `dir` is the directory relative to which paths are
+    ///   interpreted, usually the one containing the configuration file
+    pub fn from_toml(toml: &str, dir: &Path) -> Result<Self> {
+        let table = toml
+            .parse::<Table>()
+            .context("failed to parse configuration")?;
+
+        let mut config = Self {
+            dir: dir.to_path_buf(),
+            ..Default::default()
+        };
+
+        for (key, value) in &table {
+            match key.as_str() {
+                "ignore" => config.ignore = strings(value, "ignore")?,
+                "lints" => {
+                    let lints = value.as_table().context("`lints` is not a table")?;
+                    for (key, value) in lints {
+                        match key.as_str() {
+                            "only" => config.only = Some(strings(value, "lints.only")?),
+                            "enable" => config.enable = strings(value, "lints.enable")?,
+                            "disable" => config.disable = strings(value, "lints.disable")?,
+                            "dirs" => {
+                                config.lint_dirs = strings(value, "lints.dirs")?
+                                    .into_iter()
+                                    .map(|path| dir.join(path))
+                                    .collect()
+                            },
+                            "files" => {
+                                config.lint_files = strings(value, "lints.files")?
+                                    .into_iter()
+                                    .map(|path| dir.join(path))
+                                    .collect()
+                            },
+                            "severity" => {
+                                config.severities = value
+                                    .as_table()
+                                    .context("`lints.severity` is not a table")?
+                                    .iter()
+                                    .map(|(lint, severity)| {
+                                        let severity = severity.as_str().with_context(|| {
+                                            format!("severity of lint `{lint}` is not a string")
+                                        })?;
+                                        let severity =
+                                            Severity::from_str(severity).with_context(|| {
+                                                format!("severity of lint `{lint}` is invalid")
+                                            })?;
+                                        Ok((lint.to_string(), severity))
+                                    })
+                                    .collect::<Result<_>>()?
+                            },
+                            _ => return Err(anyhow!("encountered unexpected key `lints.{key}`")),
+                        }
+                    }
+                },
+                _ => return Err(anyhow!("encountered unexpected key `{key}`")),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Load the configuration contained in the file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let toml = read_to_string(path)
+            .with_context(|| format!("failed to read configuration `{}`", path.display()))?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let dir = canonicalize(dir)
+            .with_context(|| format!("failed to canonicalize path `{}`", dir.display()))?;
+        Self::from_toml(&toml, &dir)
+            .with_context(|| format!("invalid configuration `{}`", path.display()))
+    }
+
+    /// Discover the configuration applicable to the file at `path`.
+    ///
+    /// Starting at the directory containing the file, directories are
+    /// searched for a `bpflint.toml` file, moving up towards the root.
+    /// The first file found is loaded. `None` is returned if no
+    /// configuration file exists.
+    pub fn discover(path: &Path) -> Result<Option<Self>> {
+        let path = canonicalize(path)
+            .with_context(|| format!("failed to canonicalize path `{}`", path.display()))?;
+
+        for dir in path.ancestors().skip(1) {
+            let config_path = dir.join(Self::FILE_NAME);
+            if config_path.try_exists().with_context(|| {
+                format!("failed to check existence of `{}`", config_path.display())
+            })? {
+                return Self::from_file(&config_path).map(Some)
+            }
+        }
+        Ok(None)
+    }
+
+    /// Check whether the file at `path` is excluded from linting by
+    /// means of an `ignore` pattern.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        if self.ignore.is_empty() {
+            return false
+        }
+
+        let Ok(path) = canonicalize(path) else {
+            return false
+        };
+        let Ok(path) = path.strip_prefix(&self.dir) else {
+            return false
+        };
+
+        // A pattern matching any of the path's ancestors (excluding the
+        // empty one) excludes the path itself as well.
+        path.ancestors()
+            .filter(|path| !path.as_os_str().is_empty())
+            .any(|path| {
+                self.ignore
+                    .iter()
+                    .any(|pattern| matches_glob(pattern, path))
+            })
+    }
+
+    /// Create a [`Linter`] as described by this configuration.
+    ///
+    /// All built-in lints are used, along with custom lints from the
+    /// configured files and directories, restricted to the selected
+    /// ones. Lints not [enabled by default][crate::LintMeta::enabled_by_default]
+    /// are only used if selected explicitly, via `only` or `enable`. An
+    /// error is reported if the configuration refers to an unknown
+    /// lint.
+    pub fn linter(&self) -> Result<Linter> {
+        let mut linter = Linter::with_all_lints()?;
+        for dir in &self.lint_dirs {
+            for lint in Lint::load_dir(dir)? {
+                let () = linter.add_lint(lint)?;
+            }
+        }
+        for path in &self.lint_files {
+            let lint = Lint::from_file(path)
+                .with_context(|| format!("failed to load lint `{}`", path.display()))?;
+            let () = linter.add_lint(lint)?;
+        }
+        for (name, severity) in &self.severities {
+            let () = linter
+                .set_severity(name, *severity)
+                .with_context(|| format!("failed to overwrite severity of `{name}`"))?;
+        }
+
+        // Validate all lint names before narrowing down the set, so
+        // that typos don't go unnoticed.
+        let names = [
+            ("select", self.only.as_deref().unwrap_or_default()),
+            ("enable", &self.enable),
+            ("disable", &self.disable),
+        ];
+        for (action, names) in names {
+            for name in names {
+                if !linter.lints().any(|lint| lint.name == *name) {
+                    return Err(anyhow!("lint `{name}` is unknown")
+                        .context(format!("failed to {action} lint `{name}`")))
+                }
+            }
+        }
+
+        let () = linter.retain_lints(|lint| {
+            let name = &lint.name;
+            let selected = match &self.only {
+                Some(only) => only.contains(name),
+                None => lint.enabled_by_default,
+            };
+            (selected && !self.disable.contains(name)) || self.enable.contains(name)
+        });
+        Ok(linter)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::create_dir_all;
+    use std::fs::write;
+
+    use indoc::indoc;
+
+    use tempfile::tempdir;
+
+
+    /// Check that our glob matching works as expected.
+    #[test]
+    fn glob_matching() {
+        assert!(matches_glob("foo.bpf.c", Path::new("foo.bpf.c")));
+        assert!(!matches_glob("foo.bpf.c", Path::new("bar/foo.bpf.c")));
+        assert!(matches_glob("*.bpf.c", Path::new("foo.bpf.c")));
+        assert!(!matches_glob("*.bpf.c", Path::new("foo.c")));
+        assert!(matches_glob("fo?.bpf.c", Path::new("foo.bpf.c")));
+        assert!(!matches_glob("fo?.bpf.c", Path::new("fo.bpf.c")));
+        assert!(matches_glob(
+            "**/*.skel.bpf.c",
+            Path::new("a/b/x.skel.bpf.c")
+        ));
+        assert!(matches_glob("**/*.skel.bpf.c", Path::new("x.skel.bpf.c")));
+        assert!(matches_glob("vendor/**", Path::new("vendor/a/b.bpf.c")));
+        assert!(matches_glob(
+            "./vendor/*/b.bpf.c",
+            Path::new("vendor/a/b.bpf.c")
+        ));
+        assert!(!matches_glob("vendor/*", Path::new("vendor/a/b.bpf.c")));
+        assert!(matches_glob("a/**/c", Path::new("a/c")));
+        assert!(matches_glob("a/**/c", Path::new("a/b1/b2/c")));
+        assert!(matches_glob("naïv?", Path::new("naïve")));
+    }
+
+    /// Check that we can parse a configuration.
+    #[test]
+    fn config_parsing() {
+        let toml = indoc! { r#"
+          ignore = ["vendor", "**/*.skel.bpf.c"]
+
+          [lints]
+          enable = ["syntax-error"]
+          disable = ["unstable-attach-point"]
+          dirs = ["lints"]
+          files = ["../foo.scm"]
+
+          [lints.severity]
+          probe-read = "error"
+        "# };
+        let config = Config::from_toml(toml, Path::new("/project")).unwrap();
+        assert_eq!(config.dir, Path::new("/project"));
+        assert_eq!(config.ignore, ["vendor", "**/*.skel.bpf.c"]);
+        assert_eq!(config.enable, ["syntax-error"]);
+        assert_eq!(config.disable, ["unstable-attach-point"]);
+        assert_eq!(config.lint_dirs, [Path::new("/project/lints")]);
+        assert_eq!(config.lint_files, [Path::new("/project/../foo.scm")]);
+        assert_eq!(
+            config.severities,
+            [("probe-read".to_string(), Severity::Error)]
+        );
+
+        let config = Config::from_toml("", Path::new("/project")).unwrap();
+        assert!(config.disable.is_empty());
+    }
+
+    /// Check that invalid configurations are rejected.
+    #[test]
+    fn invalid_config_parsing() {
+        let toml = "foo = 42";
+        let err = Config::from_toml(toml, Path::new("")).unwrap_err();
+        assert_eq!(err.to_string(), "encountered unexpected key `foo`");
+
+        let toml = "[lints]\nenabled = []";
+        let err = Config::from_toml(toml, Path::new("")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "encountered unexpected key `lints.enabled`"
+        );
+
+        let toml = "[lints]\nenable = \"probe-read\"";
+        let err = Config::from_toml(toml, Path::new("")).unwrap_err();
+        assert_eq!(err.to_string(), "`lints.enable` is not an array");
+
+        let toml = "ignore = \"vendor\"";
+        let err = Config::from_toml(toml, Path::new("")).unwrap_err();
+        assert_eq!(err.to_string(), "`ignore` is not an array");
+
+        let toml = "[lints.severity]\nprobe-read = \"fatal\"";
+        let err = Config::from_toml(toml, Path::new("")).unwrap_err();
+        assert_eq!(err.to_string(), "severity of lint `probe-read` is invalid");
+
+        let toml = "ignore = [";
+        let err = Config::from_toml(toml, Path::new("")).unwrap_err();
+        assert_eq!(err.to_string(), "failed to parse configuration");
+    }
+
+    /// Check that we discover the closest configuration file and honor
+    /// its `ignore` patterns.
+    #[test]
+    fn config_discovery() {
+        let root = tempdir().unwrap();
+        let root = canonicalize(root.path()).unwrap();
+        let sub = root.join("sub");
+        let nested = sub.join("nested");
+        let () = create_dir_all(&nested).unwrap();
+        let () = write(root.join(Config::FILE_NAME), "ignore = [\"sub/vendor\"]").unwrap();
+        let () = write(sub.join(Config::FILE_NAME), "ignore = [\"*.skel.bpf.c\"]").unwrap();
+        let () = write(root.join("foo.bpf.c"), "").unwrap();
+        let () = write(nested.join("foo.bpf.c"), "").unwrap();
+        let () = write(sub.join("foo.skel.bpf.c"), "").unwrap();
+
+        let config = Config::discover(&root.join("foo.bpf.c")).unwrap().unwrap();
+        assert_eq!(config.dir, root);
+        assert!(!config.is_ignored(&root.join("foo.bpf.c")));
+
+        let config = Config::discover(&nested.join("foo.bpf.c"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.dir, sub);
+        assert!(!config.is_ignored(&nested.join("foo.bpf.c")));
+        assert!(config.is_ignored(&sub.join("foo.skel.bpf.c")));
+
+        let vendor = sub.join("vendor").join("lib");
+        let () = create_dir_all(&vendor).unwrap();
+        let () = write(vendor.join("foo.bpf.c"), "").unwrap();
+        let config = Config::discover(&root.join("foo.bpf.c")).unwrap().unwrap();
+        assert!(config.is_ignored(&vendor.join("foo.bpf.c")));
+    }
+
+    /// Check that we create a `Linter` honoring the configuration.
+    #[test]
+    fn config_linter_creation() {
+        let dir = tempdir().unwrap();
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifier) @function (#eq? @function "foo")
+              (#set! "message" "foo")
+          )
+        "# };
+        let () = write(dir.path().join("foo.scm"), lint).unwrap();
+
+        let toml = indoc! { r#"
+          [lints]
+          disable = ["probe-read"]
+          files = ["foo.scm"]
+
+          [lints.severity]
+          foo = "error"
+        "# };
+        let config = Config::from_toml(toml, dir.path()).unwrap();
+        let linter = config.linter().unwrap();
+        assert!(linter.lints().all(|lint| lint.name != "probe-read"));
+
+        let matches = linter.lint(b"foo();\nbpf_probe_read(a, b, c);").unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(matches[0].lint_name, "foo");
+        assert_eq!(matches[0].severity, Severity::Error);
+
+        let toml = indoc! { r#"
+          [lints]
+          disable = ["does-not-exist"]
+        "# };
+        let config = Config::from_toml(toml, dir.path()).unwrap();
+        let err = config.linter().unwrap_err();
+        assert_eq!(err.to_string(), "failed to disable lint `does-not-exist`");
+    }
+
+    /// Check that lint selection via `only`, `enable`, and `disable`
+    /// works as expected.
+    #[test]
+    fn config_lint_selection() {
+        fn names(config: &Config) -> Vec<String> {
+            let linter = config.linter().unwrap();
+            linter.lints().map(|lint| lint.name.clone()).collect()
+        }
+
+        let toml = indoc! { r#"
+          [lints]
+          only = ["probe-read"]
+        "# };
+        let mut config = Config::from_toml(toml, Path::new("")).unwrap();
+        assert_eq!(
+            config.only.as_deref(),
+            Some(["probe-read".to_string()].as_slice())
+        );
+        assert_eq!(names(&config), ["probe-read"]);
+
+        config.disable.push("probe-read".to_string());
+        assert!(names(&config).is_empty());
+
+        config.enable.push("probe-read".to_string());
+        config.enable.push("unstable-attach-point".to_string());
+        assert_eq!(names(&config), ["probe-read", "unstable-attach-point"]);
+
+        let config = Config::default();
+        assert!(!names(&config).contains(&"syntax-error".to_string()));
+
+        let config = Config {
+            enable: vec!["syntax-error".to_string()],
+            ..Default::default()
+        };
+        assert!(names(&config).contains(&"syntax-error".to_string()));
+
+        let config = Config {
+            only: Some(vec!["syntax-error".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(names(&config), ["syntax-error"]);
+
+        let config = Config {
+            only: Some(vec!["probe-reed".to_string()]),
+            ..Default::default()
+        };
+        let err = config.linter().unwrap_err();
+        assert_eq!(err.to_string(), "failed to select lint `probe-reed`");
+
+        let config = Config {
+            enable: vec!["probe-reed".to_string()],
+            ..Default::default()
+        };
+        let err = config.linter().unwrap_err();
+        assert_eq!(err.to_string(), "failed to enable lint `probe-reed`");
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::ops;
+use std::str;
+
+use tracing::warn;
+
+use tree_sitter::Node;
+use tree_sitter::Tree;
+
+
+/// The kind of a directive, determining its scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    /// `disable=`: the next syntax node or, if trailing code on the
+    /// same line, the comment's line.
+    Disable,
+    /// `disable-next-line=`: the line following the comment.
+    DisableNextLine,
+    /// `disable-file=`: the entire file.
+    DisableFile,
+    /// `disable-start=`: everything up to a matching `disable-end=`.
+    DisableStart,
+    /// `disable-end=`: terminates a region started by `disable-start=`.
+    DisableEnd,
+}
+
+
+/// A `bpflint:` directive contained in a source code comment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Directive {
+    /// The kind of directive.
+    kind: Kind,
+    /// The names of the lints the directive refers to.
+    lints: Vec<String>,
+}
+
+impl Directive {
+    /// Parse a directive from the text of a comment.
+    fn parse(comment: &str) -> Option<Self> {
+        // The comment node will still contain the actual comment
+        // syntax, unfortunately.
+        let comment = comment.trim_start_matches("//");
+        let comment = comment.trim_start_matches("/*");
+        let comment = comment.trim_end_matches("*/");
+        let comment = comment.trim();
+
+        let directive = comment.strip_prefix("bpflint:")?.trim();
+        let (kind, lints) = directive.split_once('=')?;
+        let kind = match kind {
+            "disable" => Kind::Disable,
+            "disable-next-line" => Kind::DisableNextLine,
+            "disable-file" => Kind::DisableFile,
+            "disable-start" => Kind::DisableStart,
+            "disable-end" => Kind::DisableEnd,
+            _ => return None,
+        };
+        let lints = lints
+            .split(',')
+            .map(str::trim)
+            .filter(|lint| !lint.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if lints.is_empty() {
+            return None
+        }
+
+        Some(Self { kind, lints })
+    }
+
+    /// Parse a directive from a syntax node, if it is a comment
+    /// containing one.
+    fn from_node(node: Node, code: &[u8]) -> Option<Self> {
+        if node.kind() != "comment" {
+            return None
+        }
+
+        let comment = &code[node.start_byte()..node.end_byte()];
+        if let Ok(comment) = str::from_utf8(comment) {
+            Self::parse(comment)
+        } else {
+            // If it's not valid UTF-8 it can't be a comment for us to
+            // consider.
+            warn!(
+                "encountered invalid UTF-8 in code comment at bytes `{}..{}`",
+                node.start_byte(),
+                node.end_byte()
+            );
+            None
+        }
+    }
+
+    /// Check whether the directive refers to the lint `lint_name`.
+    fn applies_to(&self, lint_name: &str) -> bool {
+        self.lints.iter().any(|lint| applies_to(lint, lint_name))
+    }
+}
+
+
+/// Check whether the lint name `lint`, as used in a directive, refers to
+/// the lint `lint_name`.
+fn applies_to(lint: &str, lint_name: &str) -> bool {
+    lint == "all" || lint == lint_name
+}
+
+/// Check whether `node` is preceded by code on the line it starts on.
+fn is_trailing(node: Node, code: &[u8]) -> bool {
+    let start = node.start_byte();
+    let line_start = code[..start]
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |idx| idx + 1);
+    !code[line_start..start].iter().all(u8::is_ascii_whitespace)
+}
+
+/// Retrieve all directives contained in `tree`, in source code order.
+fn directives<'tree>(tree: &'tree Tree, code: &[u8]) -> Vec<(Node<'tree>, Directive)> {
+    let mut directives = Vec::new();
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let node = cursor.node();
+        if let Some(directive) = Directive::from_node(node, code) {
+            let () = directives.push((node, directive));
+        }
+
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue
+        }
+
+        loop {
+            if !cursor.goto_parent() {
+                break 'walk directives
+            }
+            if cursor.goto_next_sibling() {
+                break
+            }
+        }
+    }
+}
+
+/// Retrieve the directives contained in `tree` that may affect code
+/// other than the syntax node following them, in source code order.
+pub(crate) fn non_local_directives(tree: &Tree, code: &[u8]) -> Vec<Directive> {
+    directives(tree, code)
+        .into_iter()
+        .filter(|(node, directive)| directive.kind != Kind::Disable || is_trailing(*node, code))
+        .map(|(_node, directive)| directive)
+        .collect()
+}
+
+/// Walk the syntax tree, checking if a comment node that disable the
+/// given lint is present.
+fn is_node_disabled(lint_name: &str, mut node: Node, code: &[u8]) -> bool {
+    loop {
+        // Walk all previous siblings of the current node.
+        if let Some(s) = node.prev_sibling() {
+            // Trailing comments refer to the line they are on, not to
+            // the node following them.
+            if !is_trailing(s, code) {
+                match Directive::from_node(s, code) {
+                    Some(directive)
+                        if directive.kind == Kind::Disable && directive.applies_to(lint_name) =>
+                    {
+                        break true
+                    },
+                    _ => (),
+                }
+            }
+        }
+
+        // Move one level up and repeat.
+        match node.parent() {
+            Some(parent) => node = parent,
+            None => break false,
+        }
+    }
+}
+
+
+/// The directives of a source file, resolved to the code they affect.
+#[derive(Debug, Default)]
+pub(crate) struct Directives {
+    /// Directives disabling lints for the entire file.
+    file: Vec<Directive>,
+    /// Directives disabling lints on individual rows, along with the
+    /// (zero-based) row they apply to.
+    rows: Vec<(usize, Directive)>,
+    /// Byte ranges in which lints are disabled, along with the
+    /// respective lint name.
+    regions: Vec<(String, ops::Range<usize>)>,
+}
+
+impl Directives {
+    /// Gather all directives contained in `tree`.
+    pub(crate) fn new(tree: &Tree, code: &[u8]) -> Self {
+        let mut slf = Self::default();
+        // The start offsets of regions not yet terminated, by lint
+        // name.
+        let mut open = BTreeMap::<String, usize>::new();
+
+        for (node, directive) in directives(tree, code) {
+            match directive.kind {
+                Kind::Disable if is_trailing(node, code) => {
+                    let () = slf.rows.push((node.start_position().row, directive));
+                },
+                // Directives applying to the next syntax node are
+                // evaluated on demand.
+                Kind::Disable => (),
+                Kind::DisableNextLine => {
+                    let () = slf.rows.push((node.end_position().row + 1, directive));
+                },
+                Kind::DisableFile => {
+                    let () = slf.file.push(directive);
+                },
+                Kind::DisableStart => {
+                    for lint in directive.lints {
+                        // Starting an already started region has no
+                        // effect.
+                        let _start = open.entry(lint).or_insert(node.end_byte());
+                    }
+                },
+                Kind::DisableEnd => {
+                    for lint in directive.lints {
+                        if let Some(start) = open.remove(&lint) {
+                            let () = slf.regions.push((lint, start..node.start_byte()));
+                        }
+                    }
+                },
+            }
+        }
+
+        // Regions that are never terminated extend to the end of the
+        // file.
+        let () = slf.regions.extend(
+            open.into_iter()
+                .map(|(lint, start)| (lint, start..code.len())),
+        );
+        slf
+    }
+
+    /// Check whether reporting of the lint `lint_name` is disabled for
+    /// `node`.
+    pub(crate) fn is_disabled(&self, lint_name: &str, node: Node, code: &[u8]) -> bool {
+        let row = node.start_position().row;
+        let byte = node.start_byte();
+
+        self.file
+            .iter()
+            .any(|directive| directive.applies_to(lint_name))
+            || self
+                .rows
+                .iter()
+                .any(|(row_, directive)| *row_ == row && directive.applies_to(lint_name))
+            || self
+                .regions
+                .iter()
+                .any(|(lint, bytes)| applies_to(lint, lint_name) && bytes.contains(&byte))
+            || is_node_disabled(lint_name, node, code)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Check that we parse directives as expected.
+    #[test]
+    fn directive_parsing() {
+        let directive = Directive::parse("/* bpflint: disable=foo */").unwrap();
+        assert_eq!(directive.kind, Kind::Disable);
+        assert_eq!(directive.lints, vec!["foo"]);
+
+        let directive = Directive::parse("// bpflint: disable-next-line=foo, bar,baz").unwrap();
+        assert_eq!(directive.kind, Kind::DisableNextLine);
+        assert_eq!(directive.lints, vec!["foo", "bar", "baz"]);
+
+        let directive = Directive::parse("//bpflint:disable-file=all").unwrap();
+        assert_eq!(directive.kind, Kind::DisableFile);
+        assert_eq!(directive.lints, vec!["all"]);
+
+        assert_eq!(
+            Directive::parse("/* bpflint: disable-start=foo */")
+                .unwrap()
+                .kind,
+            Kind::DisableStart
+        );
+        assert_eq!(
+            Directive::parse("/* bpflint: disable-end=foo */")
+                .unwrap()
+                .kind,
+            Kind::DisableEnd
+        );
+
+        assert_eq!(Directive::parse("/* bpflint: disable= */"), None);
+        assert_eq!(Directive::parse("/* bpflint: disable=, */"), None);
+        assert_eq!(Directive::parse("/* bpflint: disable */"), None);
+        assert_eq!(Directive::parse("/* bpflint: disable-line=foo */"), None);
+        assert_eq!(Directive::parse("/* disable=foo */"), None);
+    }
+}
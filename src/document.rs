@@ -0,0 +1,539 @@
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::ops;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::ensure;
+
+use tree_sitter::InputEdit;
+use tree_sitter::Parser;
+use tree_sitter::Tree;
+
+use crate::LintMatch;
+use crate::Linter;
+use crate::Point;
+use crate::directive::Directive;
+use crate::directive::non_local_directives;
+use crate::lint::parser;
+use crate::lint::sort_matches;
+
+
+/// An edit replacing a range of a [`Document`]'s source code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The byte range of the code to replace.
+    pub bytes: ops::Range<usize>,
+    /// The code to replace the range with.
+    pub text: Vec<u8>,
+}
+
+
+/// Determine the point of the byte at offset `byte` in `code`.
+fn point_at(code: &[u8], byte: usize) -> Point {
+    let code = &code[..byte];
+    let line_start = code
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |idx| idx + 1);
+    Point {
+        row: code[..line_start].iter().filter(|b| **b == b'\n').count(),
+        col: byte - line_start,
+    }
+}
+
+/// Determine the point reached after inserting `text` at `point`.
+fn advance(point: Point, text: &[u8]) -> Point {
+    match text.iter().rposition(|b| *b == b'\n') {
+        Some(idx) => Point {
+            row: point.row + text.iter().filter(|b| **b == b'\n').count(),
+            col: text.len() - idx - 1,
+        },
+        None => Point {
+            row: point.row,
+            col: point.col + text.len(),
+        },
+    }
+}
+
+/// Map a byte offset in code before `edit` to the corresponding offset
+/// after it.
+///
+/// Offsets inside of the replaced range are mapped to its new end.
+fn shift_byte(byte: usize, edit: &InputEdit) -> usize {
+    if byte >= edit.old_end_byte {
+        byte - edit.old_end_byte + edit.new_end_byte
+    } else if byte > edit.start_byte {
+        edit.new_end_byte
+    } else {
+        byte
+    }
+}
+
+/// Map a point at or after the end of the range replaced by `edit` to
+/// the corresponding point after the edit.
+fn shift_point(point: Point, edit: &InputEdit) -> Point {
+    let old_end = Point::from(edit.old_end_position);
+    let new_end = Point::from(edit.new_end_position);
+    if point.row == old_end.row {
+        Point {
+            row: new_end.row,
+            col: point.col - old_end.col + new_end.col,
+        }
+    } else {
+        Point {
+            row: point.row - old_end.row + new_end.row,
+            col: point.col,
+        }
+    }
+}
+
+/// Sort and merge overlapping and adjacent ranges.
+fn merge_ranges(mut ranges: Vec<ops::Range<usize>>) -> Vec<ops::Range<usize>> {
+    let () = ranges.sort_by_key(|range| range.start);
+    let mut merged = Vec::<ops::Range<usize>>::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn contains(outer: &ops::Range<usize>, inner: &ops::Range<usize>) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+
+/// A source code document that can be edited and linted repeatedly.
+///
+/// In contrast to [`Linter::lint`], a `Document` keeps the syntax tree
+/// of its code around. After edits, the code is reparsed incrementally
+/// and lints are only re-run over the top-level declarations affected
+/// by the changes, which keeps latency low when working with large
+/// inputs, e.g., in an editor.
+///
+/// ```
+/// # use bpflint::Document;
+/// # use bpflint::Linter;
+/// # use bpflint::TextEdit;
+/// let linter = Linter::new().unwrap();
+/// let mut document = Document::new("void f(void) { bpf_probe_read(a, b, c); }").unwrap();
+/// assert_eq!(document.lint(&linter).unwrap().len(), 1);
+///
+/// let edit = TextEdit {
+///     bytes: 15..29,
+///     text: b"bpf_probe_read_kernel".to_vec(),
+/// };
+/// let () = document.edit(&edit).unwrap();
+/// assert_eq!(document.lint(&linter).unwrap().len(), 0);
+/// ```
+pub struct Document {
+    /// The document's source code.
+    code: Vec<u8>,
+    /// The parser used for (re-)parsing the source code.
+    parser: Parser,
+    /// The syntax tree of the source code, as of the last parse, with
+    /// subsequent edits applied.
+    tree: Tree,
+    /// Byte ranges of the source code that were edited since the last
+    /// parse.
+    edited: Vec<ops::Range<usize>>,
+    /// Byte ranges that need to be re-linted.
+    dirty: Vec<ops::Range<usize>>,
+    /// The lint matches as of the last lint, if any.
+    matches: Option<Vec<LintMatch>>,
+    /// The directives affecting code beyond the syntax node following
+    /// them, as of the last parse.
+    directives: Vec<Directive>,
+}
+
+impl Document {
+    /// Create a new `Document` with the provided source code.
+    pub fn new(code: impl Into<Vec<u8>>) -> Result<Self> {
+        let code = code.into();
+        let mut parser = parser()?;
+        let tree = parser
+            .parse(&code, None)
+            .context("failed to parse source code")?;
+        let directives = non_local_directives(&tree, &code);
+
+        let slf = Self {
+            code,
+            parser,
+            tree,
+            edited: Vec::new(),
+            dirty: Vec::new(),
+            matches: None,
+            directives,
+        };
+        Ok(slf)
+    }
+
+    /// Retrieve the document's current source code.
+    pub fn code(&self) -> &[u8] {
+        &self.code
+    }
+
+    /// Apply an edit to the document's source code.
+    ///
+    /// Edits are expressed in terms of the source code as it is at the
+    /// time of the call, i.e., with all previous edits applied.
+    pub fn edit(&mut self, edit: &TextEdit) -> Result<()> {
+        let TextEdit { bytes, text } = edit;
+        ensure!(
+            bytes.start <= bytes.end && bytes.end <= self.code.len(),
+            "edit range {bytes:?} is out of bounds"
+        );
+
+        let start_position = point_at(&self.code, bytes.start);
+        let old_end_position = point_at(&self.code, bytes.end);
+        let new_end_position = advance(start_position, text);
+        let edit = InputEdit {
+            start_byte: bytes.start,
+            old_end_byte: bytes.end,
+            new_end_byte: bytes.start + text.len(),
+            start_position: start_position.into(),
+            old_end_position: old_end_position.into(),
+            new_end_position: new_end_position.into(),
+        };
+
+        let _replaced = self.code.splice(bytes.clone(), text.iter().copied());
+        let () = self.tree.edit(&edit);
+
+        for range in self.edited.iter_mut().chain(self.dirty.iter_mut()) {
+            *range = shift_byte(range.start, &edit)..shift_byte(range.end, &edit);
+        }
+        let () = self.edited.push(edit.start_byte..edit.new_end_byte);
+
+        if let Some(matches) = &mut self.matches {
+            // Matches overlapping the edit are simply discarded; they
+            // will be re-discovered when linting next.
+            let () = matches.retain_mut(|m| {
+                let range = &mut m.range;
+                if range.bytes.end <= edit.start_byte {
+                    true
+                } else if range.bytes.start >= edit.old_end_byte {
+                    range.bytes =
+                        shift_byte(range.bytes.start, &edit)..shift_byte(range.bytes.end, &edit);
+                    range.start_point = shift_point(range.start_point, &edit);
+                    range.end_point = shift_point(range.end_point, &edit);
+                    true
+                } else {
+                    false
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Reparse the source code if it was edited since the last parse,
+    /// marking affected ranges as dirty.
+    fn reparse(&mut self) -> Result<()> {
+        if self.edited.is_empty() {
+            return Ok(())
+        }
+
+        let tree = self
+            .parser
+            .parse(&self.code, Some(&self.tree))
+            .context("failed to parse source code")?;
+        // Changes in syntactic structure may extend beyond the edited
+        // ranges, while edits may not necessarily change the structure
+        // (e.g., when renaming an identifier), so we need both.
+        let changed = self
+            .tree
+            .changed_ranges(&tree)
+            .map(|range| range.start_byte..range.end_byte)
+            .chain(self.edited.drain(..))
+            .collect::<Vec<_>>();
+
+        // Lints operate on syntactic constructs that may be larger than
+        // the changed ranges and disable directives may be located
+        // elsewhere. Hence, we re-lint all affected top-level
+        // declarations as well as the ones following them, which may
+        // be the subject of a changed directive.
+        let root = tree.root_node();
+        for range in changed {
+            let mut child = root.first_child_for_byte(range.start);
+            while let Some(node) = child {
+                let () = self.dirty.push(node.start_byte()..node.end_byte());
+                if node.start_byte() > range.end {
+                    break
+                }
+                child = node.next_sibling();
+            }
+        }
+
+        // Directives may affect code arbitrarily far away from them
+        // (e.g., the entire file). If any of them changed, we have to
+        // re-lint everything.
+        let directives = non_local_directives(&tree, &self.code);
+        if directives != self.directives {
+            self.matches = None;
+            self.directives = directives;
+        }
+
+        self.tree = tree;
+        Ok(())
+    }
+
+    /// Check whether the document's source code contains syntax errors,
+    /// i.e., parts that could not be parsed cleanly.
+    pub fn has_syntax_errors(&mut self) -> Result<bool> {
+        let () = self.reparse()?;
+        Ok(self.tree.root_node().has_error())
+    }
+
+    /// Lint the document using the provided [`Linter`].
+    ///
+    /// Matches are reported in source code order.
+    ///
+    /// Only the parts of the document that changed since the last
+    /// invocation are re-linted. As such, the same `Linter` should be
+    /// used for all invocations on a `Document`.
+    pub fn lint(&mut self, linter: &Linter) -> Result<&[LintMatch]> {
+        let () = self.reparse()?;
+        let dirty = merge_ranges(self.dirty.drain(..).collect());
+
+        let matches = match self.matches.take() {
+            None => linter.lint_tree(&self.tree, &self.code, None)?,
+            Some(mut matches) => {
+                let () =
+                    matches.retain(|m| !dirty.iter().any(|range| contains(range, &m.range.bytes)));
+                for range in dirty {
+                    let new = linter.lint_tree(&self.tree, &self.code, Some(range.clone()))?;
+                    let () = matches
+                        .extend(new.into_iter().filter(|m| contains(&range, &m.range.bytes)));
+                }
+                matches
+            },
+        };
+
+        let matches = self.matches.insert(matches);
+        let () = sort_matches(matches);
+        Ok(matches)
+    }
+}
+
+impl Debug for Document {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self {
+            code,
+            parser: _,
+            tree,
+            edited,
+            dirty,
+            matches,
+            directives,
+        } = self;
+
+        f.debug_struct("Document")
+            .field("code", &String::from_utf8_lossy(code))
+            .field("tree", tree)
+            .field("edited", edited)
+            .field("dirty", dirty)
+            .field("matches", matches)
+            .field("directives", directives)
+            .finish_non_exhaustive()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+
+
+    /// Apply an edit replacing the first occurrence of `old` with `new`
+    /// to `document`.
+    fn replace(document: &mut Document, old: &str, new: &str) {
+        let code = str::from_utf8(document.code()).unwrap();
+        let start = code.find(old).unwrap();
+        let edit = TextEdit {
+            bytes: start..start + old.len(),
+            text: new.as_bytes().to_vec(),
+        };
+        let () = document.edit(&edit).unwrap();
+    }
+
+    /// Check that incremental linting yields the same results as linting
+    /// from scratch.
+    fn check(document: &mut Document, linter: &Linter) {
+        let expected = linter.lint(document.code()).unwrap();
+        let matches = document.lint(linter).unwrap();
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (&m.lint_name, &m.range))
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|m| (&m.lint_name, &m.range))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    /// Check that we can determine points of byte offsets.
+    #[test]
+    fn point_calculation() {
+        let code = b"ab\ncd\n\nef";
+        assert_eq!(point_at(code, 0), Point { row: 0, col: 0 });
+        assert_eq!(point_at(code, 2), Point { row: 0, col: 2 });
+        assert_eq!(point_at(code, 3), Point { row: 1, col: 0 });
+        assert_eq!(point_at(code, 7), Point { row: 3, col: 0 });
+        assert_eq!(point_at(code, 9), Point { row: 3, col: 2 });
+
+        let point = Point { row: 1, col: 2 };
+        assert_eq!(advance(point, b"xyz"), Point { row: 1, col: 5 });
+        assert_eq!(advance(point, b"x\nyz"), Point { row: 2, col: 2 });
+        assert_eq!(advance(point, b"x\n"), Point { row: 2, col: 0 });
+    }
+
+    /// Check that we merge ranges as expected.
+    #[test]
+    fn range_merging() {
+        let ranges = vec![10..12, 0..2, 1..3, 3..5, 7..8];
+        assert_eq!(merge_ranges(ranges), vec![0..5, 7..8, 10..12]);
+    }
+
+    /// Check that we detect syntax errors introduced by edits.
+    #[test]
+    fn syntax_error_detection() {
+        let mut document = Document::new("int x = 1;").unwrap();
+        assert!(!document.has_syntax_errors().unwrap());
+
+        let () = replace(&mut document, "1", "");
+        assert!(document.has_syntax_errors().unwrap());
+
+        let () = replace(&mut document, "= ;", "= 2;");
+        assert!(!document.has_syntax_errors().unwrap());
+    }
+
+    /// Make sure that we reject out of bounds edits.
+    #[test]
+    fn invalid_edit() {
+        let mut document = Document::new("int x;").unwrap();
+        let edit = TextEdit {
+            bytes: 4..7,
+            text: Vec::new(),
+        };
+        let err = document.edit(&edit).unwrap_err();
+        assert_eq!(err.to_string(), "edit range 4..7 is out of bounds");
+    }
+
+    /// Check that incremental re-linting after edits produces the same
+    /// matches as linting the edited code from scratch.
+    #[test]
+    fn incremental_linting() {
+        let linter = Linter::with_lints(["probe-read"]).unwrap();
+        let code = indoc! { r#"
+          void f(void) {
+            bpf_probe_read(a, b, c);
+          }
+
+          void g(void) {
+            int x = 1;
+          }
+
+          void h(void) {
+            bpf_probe_read(d, e, f);
+          }
+        "# };
+        let mut document = Document::new(code).unwrap();
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 2);
+
+        // Introduce a new match in a function without any.
+        let () = replace(&mut document, "int x = 1;", "bpf_probe_read(x, y,\n    z);");
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 3);
+
+        // Shift matches in later functions by inserting lines.
+        let () = replace(
+            &mut document,
+            "void f(void) {\n",
+            "void f(void) {\n\n\n  int y;\n",
+        );
+        let () = check(&mut document, &linter);
+
+        // Removing a match by renaming without structural change.
+        let () = replace(&mut document, "bpf_probe_read(a", "bpf_probe_reax(a");
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 2);
+
+        // Multiple edits in one go, before linting again.
+        let () = replace(&mut document, "bpf_probe_reax(a", "bpf_probe_read(a");
+        let () = replace(&mut document, "void h(void) {", "void hh(void) {");
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 3);
+
+        // Break and then restore the syntax.
+        let () = replace(&mut document, "void g(void) {", "void g(void) ");
+        let () = check(&mut document, &linter);
+        let () = replace(&mut document, "void g(void) ", "void g(void) {");
+        let () = check(&mut document, &linter);
+    }
+
+    /// Check that changes to disable directives are picked up, even if
+    /// they affect code outside of the edited declaration.
+    #[test]
+    fn incremental_directive_linting() {
+        let linter = Linter::with_lints(["probe-read"]).unwrap();
+        let code = indoc! { r#"
+          int x;
+
+          void f(void) {
+            bpf_probe_read(a, b, c);
+          }
+        "# };
+        let mut document = Document::new(code).unwrap();
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 1);
+
+        let () = replace(
+            &mut document,
+            "\nvoid f",
+            "/* bpflint: disable=probe-read */\nvoid f",
+        );
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 0);
+
+        let () = replace(&mut document, "/* bpflint: disable=probe-read */\n", "");
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 1);
+    }
+
+    /// Check that changes to directives affecting the entire file are
+    /// picked up.
+    #[test]
+    fn incremental_file_directive_linting() {
+        let linter = Linter::with_lints(["probe-read"]).unwrap();
+        let code = indoc! { r#"
+          void f(void) {
+            bpf_probe_read(a, b, c);
+          }
+
+          int x;
+        "# };
+        let mut document = Document::new(code).unwrap();
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 1);
+
+        let () = replace(
+            &mut document,
+            "int x;",
+            "int x; /* bpflint: disable-file=probe-read */",
+        );
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 0);
+
+        let () = replace(&mut document, "disable-file=", "disable-start=");
+        let () = check(&mut document, &linter);
+        assert_eq!(document.lint(&linter).unwrap().len(), 1);
+    }
+}
@@ -21,13 +21,49 @@
 //! In the above examples, none of the instances of `bpf_probe_read`
 //! will be flagged.
 //!
-//! The directive `bpflint: disable=all` acts as a catch-all, disabling
-//! reporting of all lints.
+//! The scope of a directive depends on its kind and placement:
+//! - `disable=` on a line of its own applies to the syntax node
+//!   (statement, block, function, ...) directly following it,
+//!   including everything nested inside of it
+//! - `disable=` following code on the same line applies to matches
+//!   starting on this line only
+//! - `disable-next-line=` applies to matches starting on the line
+//!   after the comment
+//! - `disable-file=` applies to the entire file, irrespective of where
+//!   it is placed
+//! - `disable-start=` applies to all matches starting after it and up
+//!   to a `disable-end=` directive naming the same lint, or the end of
+//!   the file if there is none
+//!
+//! ```c
+//! /* bpflint: disable-file=untyped-map-member */
+//! bpf_probe_read(/* ... */); // bpflint: disable=probe-read
+//! // bpflint: disable-next-line=probe-read
+//! bpf_probe_read(/* ... */);
+//!
+//! /* bpflint: disable-start=probe-read */
+//! bpf_probe_read(/* ... */);
+//! /* bpflint: disable-end=probe-read */
+//! ```
+//!
+//! Each directive accepts a comma separated list of lint names, e.g.,
+//! `bpflint: disable=probe-read,unstable-attach-point`. The name `all`
+//! acts as a catch-all, disabling reporting of all lints.
+//!
+//! Code that could not be parsed cleanly can be reported by the
+//! `syntax-error` lint, as other lints may not cover it. Because of
+//! its noisiness, the lint is not part of the default set of lints and
+//! has to be enabled explicitly (e.g., via [`Linter::with_lints`] or
+//! [`Config::enable`]). Use [`has_syntax_errors`] to check for such
+//! code directly.
 
 #[cfg(target_arch = "wasm32")]
 #[macro_use]
 mod redefine;
 
+mod config;
+mod directive;
+mod document;
 mod lint;
 mod report;
 
@@ -36,16 +72,18 @@ use std::ops;
 
 /// A position in a multi-line text document, in terms of rows and columns.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Point {
     /// A row number in source code (zero-based).
     pub row: usize,
-    /// A column number in source code (zero-based).
+    /// A column number in source code, in bytes (zero-based).
     pub col: usize,
 }
 
 /// A range of positions in a multi-line text document, both in terms of bytes
 /// and of rows and columns.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Range {
     /// The byte range in the source code.
     pub bytes: ops::Range<usize>,
@@ -55,11 +93,71 @@ pub struct Range {
     pub end_point: Point,
 }
 
+impl Range {
+    /// Check whether the range refers to a file as a whole instead of a
+    /// location in it, as is the case for matches flagging, say, the
+    /// file's name.
+    ///
+    /// Such ranges are empty and located at the very start of the file.
+    pub(crate) fn is_file_level(&self) -> bool {
+        self.bytes == (0..0)
+    }
+
+    /// Convert the byte column of `point`, located at byte offset
+    /// `byte` in `code`, using `count` to measure the line's prefix.
+    fn convert_point(code: &[u8], point: Point, byte: usize, count: fn(&str) -> usize) -> Point {
+        let line_start = byte.saturating_sub(point.col);
+        let prefix = code.get(line_start..byte).unwrap_or_default();
+        Point {
+            row: point.row,
+            col: count(&String::from_utf8_lossy(prefix)),
+        }
+    }
+
+    fn convert_points(&self, code: &[u8], count: fn(&str) -> usize) -> (Point, Point) {
+        (
+            Self::convert_point(code, self.start_point, self.bytes.start, count),
+            Self::convert_point(code, self.end_point, self.bytes.end, count),
+        )
+    }
+
+    /// Retrieve the start and end points of the range, with columns
+    /// expressed in Unicode characters instead of bytes.
+    ///
+    /// - `code` is the source code the range refers to
+    pub fn char_points(&self, code: &[u8]) -> (Point, Point) {
+        self.convert_points(code, |s| s.chars().count())
+    }
+
+    /// Retrieve the start and end points of the range, with columns
+    /// expressed in UTF-16 code units instead of bytes, as used by
+    /// editor protocols such as the Language Server Protocol.
+    ///
+    /// - `code` is the source code the range refers to
+    pub fn utf16_points(&self, code: &[u8]) -> (Point, Point) {
+        self.convert_points(code, |s| s.encode_utf16().count())
+    }
+}
+
+pub use crate::config::Config;
+pub use crate::document::Document;
+pub use crate::document::TextEdit;
+pub use crate::lint::Lint;
+pub use crate::lint::LintExample;
 pub use crate::lint::LintMatch;
 pub use crate::lint::LintMeta;
+pub use crate::lint::Linter;
+pub use crate::lint::Severity;
 pub use crate::lint::builtin_lints;
+pub use crate::lint::has_syntax_errors;
 pub use crate::lint::lint;
+pub use crate::report::ReportOpts;
+pub use crate::report::report_github;
+pub use crate::report::report_json;
+pub use crate::report::report_sarif;
+pub use crate::report::report_short;
 pub use crate::report::report_terminal;
+pub use crate::report::report_terminal_opts;
 
 
 #[cfg(target_arch = "wasm32")]
@@ -96,3 +194,39 @@ mod wasm {
         lint_impl(code, PathBuf::from(path)).map_err(|err| format!("{err:?}"))
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Check that we can convert range columns to characters and
+    /// UTF-16 code units.
+    #[test]
+    fn range_column_conversion() {
+        let code = "x\n/* ü𝄞 */ foo();\n";
+        let start = code.find("foo").unwrap();
+        let end = start + "foo()".len();
+        let range = Range {
+            bytes: start..end,
+            start_point: Point {
+                row: 1,
+                col: start - 2,
+            },
+            end_point: Point {
+                row: 1,
+                col: end - 2,
+            },
+        };
+        assert_eq!(range.start_point.col, 13);
+
+        let (start, end) = range.char_points(code.as_bytes());
+        assert_eq!(start, Point { row: 1, col: 9 });
+        assert_eq!(end, Point { row: 1, col: 14 });
+
+        let (start, end) = range.utf16_points(code.as_bytes());
+        assert_eq!(start, Point { row: 1, col: 10 });
+        assert_eq!(end, Point { row: 1, col: 15 });
+    }
+}
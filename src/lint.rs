@@ -1,11 +1,24 @@
+use std::ffi::OsStr;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fs::read_dir;
+use std::fs::read_to_string;
+use std::ops;
+use std::path::Path;
 use std::str;
+use std::str::FromStr;
+use std::sync::Mutex;
 
 use anyhow::Context as _;
+use anyhow::Error;
 use anyhow::Result;
+use anyhow::anyhow;
 
 use tracing::warn;
 
-use tree_sitter::Node;
+use tree_sitter::CaptureQuantifier;
 use tree_sitter::Parser;
 use tree_sitter::Query;
 use tree_sitter::QueryCursor;
@@ -15,12 +28,97 @@ use tree_sitter_bpf_c::LANGUAGE;
 
 use crate::Point;
 use crate::Range;
+use crate::directive::Directives;
 
 
+/// A lint shipped as part of the library, in its source form.
+#[derive(Clone, Copy, Debug)]
+struct BuiltinLint {
+    /// The lint's name.
+    name: &'static str,
+    /// The lint's `tree-sitter` query source code.
+    src: &'static str,
+    /// The severity declared by the lint's query.
+    severity: Severity,
+    /// A one-line summary of what the lint flags.
+    summary: &'static str,
+    /// An explanation of why the flagged pattern is problematic.
+    rationale: &'static str,
+    /// Links to further information.
+    references: &'static [&'static str],
+    /// Pairs of bad and good code examples.
+    examples: &'static [(&'static str, &'static str)],
+    /// Whether the lint is part of the default set of lints.
+    enabled: bool,
+}
+
+impl BuiltinLint {
+    /// Create the meta data of the lint, without compiling it.
+    fn meta(&self) -> LintMeta {
+        let Self {
+            name,
+            src: _,
+            severity,
+            summary,
+            rationale,
+            references,
+            examples,
+            enabled,
+        } = self;
+
+        LintMeta {
+            name: name.to_string(),
+            default_severity: *severity,
+            summary: summary.to_string(),
+            rationale: rationale.to_string(),
+            references: references.iter().map(ToString::to_string).collect(),
+            examples: examples
+                .iter()
+                .map(|(bad, good)| LintExample {
+                    bad: bad.to_string(),
+                    good: good.to_string(),
+                })
+                .collect(),
+            enabled_by_default: *enabled,
+            _non_exhaustive: (),
+        }
+    }
+}
+
 mod lints {
+    use super::BuiltinLint;
+    use super::Severity;
+
     include!(concat!(env!("OUT_DIR"), "/lints.rs"));
 }
 
+/// The name of the capture designating the node to report in a match.
+const REPORT_CAPTURE: &str = "report";
+
+
+/// Create a parser for BPF C code.
+pub(crate) fn parser() -> Result<Parser> {
+    let mut parser = Parser::new();
+    let () = parser
+        .set_language(&LANGUAGE.into())
+        .context("failed to load C parser")?;
+    Ok(parser)
+}
+
+/// Sort lint matches by their location in the source code.
+pub(crate) fn sort_matches(matches: &mut [LintMatch]) {
+    // NB: We use an ad-hoc comparison rather than a proper
+    // `PartialOrd` impl for `Range`, because the latter is a bit
+    // harder to do correctly.
+    let () = matches.sort_by(|match1, match2| {
+        match1
+            .range
+            .start_point
+            .cmp(&match2.range.start_point)
+            .then_with(|| match1.range.end_point.cmp(&match2.range.end_point))
+    });
+}
+
 impl From<tree_sitter::Point> for Point {
     fn from(other: tree_sitter::Point) -> Self {
         let tree_sitter::Point { row, column } = other;
@@ -28,6 +126,13 @@ impl From<tree_sitter::Point> for Point {
     }
 }
 
+impl From<Point> for tree_sitter::Point {
+    fn from(other: Point) -> Self {
+        let Point { row, col } = other;
+        Self { row, column: col }
+    }
+}
+
 impl From<tree_sitter::Range> for Range {
     fn from(other: tree_sitter::Range) -> Self {
         let tree_sitter::Range {
@@ -45,11 +150,83 @@ impl From<tree_sitter::Range> for Range {
 }
 
 
+/// The severity of a lint match.
+///
+/// Severities are ordered, with [`Severity::Hint`] being the least and
+/// [`Severity::Error`] the most severe.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    /// A hint, e.g., concerning style.
+    Hint,
+    /// An informational finding.
+    Info,
+    /// A warning about a likely problem.
+    #[default]
+    Warning,
+    /// An error that should be fixed.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Hint => "hint",
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for Severity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hint" => Ok(Self::Hint),
+            "info" => Ok(Self::Info),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            _ => Err(anyhow!(
+                "invalid severity `{s}`; expected one of `hint`, `info`, `warning`, or `error`"
+            )),
+        }
+    }
+}
+
+
+/// An example illustrating code flagged by a lint and how to fix it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintExample {
+    /// Code that is flagged by the lint.
+    pub bad: String,
+    /// An improved version of the code that is not flagged.
+    pub good: String,
+}
+
+
 /// Meta data about a lint.
 #[derive(Clone, Debug)]
 pub struct LintMeta {
     /// The lint's name.
     pub name: String,
+    /// The severity of matches of the lint, unless overwritten.
+    pub default_severity: Severity,
+    /// A one-line summary of what the lint flags.
+    pub summary: String,
+    /// A longer explanation of why the flagged code is problematic.
+    pub rationale: String,
+    /// Links to further information, e.g., relevant man pages.
+    pub references: Vec<String>,
+    /// Examples of code flagged by the lint alongside fixed versions.
+    pub examples: Vec<LintExample>,
+    /// Whether the lint is part of the default set of lints, i.e., run
+    /// unless explicitly disabled. Lints that are not have to be
+    /// enabled explicitly.
+    pub enabled_by_default: bool,
     /// The struct is non-exhaustive and open to extension.
     #[doc(hidden)]
     pub _non_exhaustive: (),
@@ -58,134 +235,433 @@ pub struct LintMeta {
 
 /// Retrieve the list of lints shipped with the library.
 pub fn builtin_lints() -> impl ExactSizeIterator<Item = LintMeta> + DoubleEndedIterator {
-    lints::LINTS.iter().map(|(name, _code)| LintMeta {
-        name: name.to_string(),
-        _non_exhaustive: (),
-    })
+    lints::LINTS.iter().map(BuiltinLint::meta)
 }
 
 
 /// Details about a lint match.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LintMatch {
     /// The name of the lint that matched.
     pub lint_name: String,
     /// The lint's message.
     pub message: String,
+    /// The severity of the match.
+    pub severity: Severity,
     /// The code range that triggered the lint.
+    ///
+    /// The range may be empty, e.g., when flagging code missing at a
+    /// given location. An empty range at the very start of the code
+    /// (i.e., `0..0`) denotes a match concerning the file as a whole.
     pub range: Range,
 }
 
 
-/// Walk the syntax tree, checking if a comment node that disable the
-/// given lint is present.
-fn is_lint_disabled(lint_name: &str, mut node: Node, code: &[u8]) -> bool {
-    loop {
-        // Walk all previous siblings of the current node.
-        if let Some(s) = node.prev_sibling() {
-            if s.kind() == "comment" {
-                let comment = &code[s.start_byte()..s.end_byte()];
-                if let Ok(comment) = str::from_utf8(comment) {
-                    // The comment node will still contain the actual
-                    // comment syntax, unfortunately.
-                    let comment = comment.trim_start_matches("//");
-                    let comment = comment.trim_start_matches("/*");
-                    let comment = comment.trim_end_matches("*/");
-                    let comment = comment.trim();
-
-                    if let Some(comment) = comment.strip_prefix("bpflint:") {
-                        let directive = comment.trim();
-                        match directive.strip_prefix("disable=") {
-                            Some("all") => break true,
-                            Some(disable) if disable == lint_name => break true,
-                            _ => (),
-                        }
-                    }
-                } else {
-                    // If it's not valid UTF-8 it can't be a comment for
-                    // us to consider.
-                    warn!(
-                        "encountered invalid UTF-8 in code comment at bytes `{}..{}`",
-                        s.start_byte(),
-                        s.end_byte()
-                    );
+/// A lint in its compiled form, ready to be matched against syntax
+/// trees.
+///
+/// Besides the lints shipped with the library, custom lints can be
+/// created from `tree-sitter` query source code at runtime, using
+/// [`Lint::from_source`] or [`Lint::from_file`], and used via
+/// [`Linter::add_lint`]. Such lints are subject to the same
+/// requirements as built-in ones, i.e., each of their patterns has to
+/// carry a `message` property.
+#[derive(Debug)]
+pub struct Lint {
+    /// Meta data about the lint.
+    meta: LintMeta,
+    /// The severity with which to report matches.
+    severity: Severity,
+    /// The compiled `tree-sitter` query representing the lint.
+    query: Query,
+    /// The messages associated with the query's patterns, indexed by
+    /// pattern.
+    messages: Vec<String>,
+    /// The index of the `@report` capture, if any, for each of the
+    /// query's patterns.
+    reports: Vec<Option<u32>>,
+}
+
+impl Lint {
+    /// Compile the lint with the given name from its query source code.
+    ///
+    /// - `name` is the name of the lint, as used in reports and in
+    ///   `bpflint: disable=<name>` directives
+    /// - `src` is the `tree-sitter` query representing the lint
+    pub fn from_source(name: &str, src: &str) -> Result<Self> {
+        let query = Query::new(&LANGUAGE.into(), src)
+            .with_context(|| format!("{name}: failed to compile lint query"))?;
+        let messages = (0..query.pattern_count())
+            .map(|idx| {
+                let settings = query.property_settings(idx);
+                let setting = settings
+                    .iter()
+                    .find(|prop| &*prop.key == "message")
+                    .with_context(|| format!("{name}: failed to find `message` property"))?;
+                let message = setting
+                    .value
+                    .as_ref()
+                    .with_context(|| format!("{name}: `message` property has no value set"))?
+                    .to_string();
+                Ok(message)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let severity = Self::severity(name, &query)?;
+        let report_idx = query.capture_index_for_name(REPORT_CAPTURE);
+        let reports = (0..query.pattern_count())
+            .map(|idx| {
+                report_idx.filter(|report_idx| {
+                    let quantifiers = query.capture_quantifiers(idx);
+                    quantifiers[*report_idx as usize] != CaptureQuantifier::Zero
+                })
+            })
+            .collect();
+
+        let slf = Self {
+            meta: LintMeta {
+                name: name.to_string(),
+                default_severity: severity,
+                summary: String::new(),
+                rationale: String::new(),
+                references: Vec::new(),
+                examples: Vec::new(),
+                enabled_by_default: true,
+                _non_exhaustive: (),
+            },
+            severity,
+            query,
+            messages,
+            reports,
+        };
+        Ok(slf)
+    }
+
+    /// Load and compile a lint from the query source code contained in
+    /// the file at `path`.
+    ///
+    /// The lint's name is derived from the file name, with any
+    /// extension removed, i.e., a file `lints/foo-bar.scm` results in
+    /// a lint named `foo-bar`.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .with_context(|| format!("`{}` does not have a file name", path.display()))?
+            .to_str()
+            .with_context(|| format!("lint `{}` does not have valid UTF-8 name", path.display()))?;
+        let src = read_to_string(path)
+            .with_context(|| format!("failed to read lint `{}`", path.display()))?;
+        Self::from_source(name, &src)
+    }
+
+    /// Load and compile all lints contained in `*.scm` files in the
+    /// directory `dir`.
+    ///
+    /// Lints are returned in the order of their file names.
+    pub fn load_dir(dir: &Path) -> Result<Vec<Self>> {
+        let mut paths = Vec::new();
+        for result in read_dir(dir)
+            .with_context(|| format!("failed to read directory `{}`", dir.display()))?
+        {
+            let entry = result.with_context(|| {
+                format!("failed to read directory entry in `{}`", dir.display())
+            })?;
+            let path = entry.path();
+            if path.extension() == Some(OsStr::new("scm")) {
+                let () = paths.push(path);
+            }
+        }
+        // Sort for deterministic ordering irrespective of file system.
+        let () = paths.sort();
+
+        paths
+            .iter()
+            .map(|path| {
+                Self::from_file(path)
+                    .with_context(|| format!("failed to load lint `{}`", path.display()))
+            })
+            .collect()
+    }
+
+    /// Retrieve meta data about the lint.
+    #[inline]
+    pub fn meta(&self) -> &LintMeta {
+        &self.meta
+    }
+
+    /// Compile a lint shipped as part of the library.
+    fn from_builtin(lint: &BuiltinLint) -> Result<Self> {
+        let mut slf = Self::from_source(lint.name, lint.src)?;
+        slf.meta = lint.meta();
+        Ok(slf)
+    }
+
+    /// Determine the severity of a lint as declared by the `severity`
+    /// property of its query.
+    ///
+    /// The severity applies to the lint as a whole and may be declared
+    /// by any of the query's patterns. If none does, matches are
+    /// reported as [`Severity::Warning`].
+    fn severity(name: &str, query: &Query) -> Result<Severity> {
+        let mut severity = None;
+        for idx in 0..query.pattern_count() {
+            let settings = query.property_settings(idx);
+            if let Some(setting) = settings.iter().find(|prop| &*prop.key == "severity") {
+                let value = setting
+                    .value
+                    .as_ref()
+                    .with_context(|| format!("{name}: `severity` property has no value set"))?;
+                let value = Severity::from_str(value)
+                    .with_context(|| format!("{name}: `severity` property is invalid"))?;
+
+                match severity {
+                    Some(severity) if severity != value => {
+                        return Err(anyhow!(
+                            "{name}: patterns declare conflicting `severity` properties"
+                        ));
+                    },
+                    _ => severity = Some(value),
                 }
             }
         }
+        Ok(severity.unwrap_or_default())
+    }
 
-        // Move one level up and repeat.
-        match node.parent() {
-            Some(parent) => node = parent,
-            None => break false,
+    /// Find all matches of the lint in `tree`, optionally restricted to
+    /// those intersecting the byte range `bytes`, that are not disabled
+    /// by `directives`.
+    fn matches(
+        &self,
+        tree: &Tree,
+        code: &[u8],
+        bytes: Option<ops::Range<usize>>,
+        directives: &Directives,
+    ) -> Result<Vec<LintMatch>> {
+        let Self {
+            meta: LintMeta { name, .. },
+            severity,
+            query,
+            messages,
+            reports,
+        } = self;
+
+        let mut query_cursor = QueryCursor::new();
+        if let Some(bytes) = bytes {
+            let _cursor = query_cursor.set_byte_range(bytes);
         }
+        let mut results = Vec::new();
+        let mut matches = query_cursor.matches(query, tree.root_node(), code);
+        while let Some(m) = matches.next() {
+            // Each pattern carries its own message, which we resolved
+            // when compiling the lint.
+            let message = &messages[m.pattern_index];
+            let report = reports[m.pattern_index];
+            for capture in m.captures {
+                // If the pattern designates a capture to report, all
+                // other captures merely serve as inputs to predicates.
+                if report.is_some_and(|report| capture.index != report) {
+                    continue;
+                }
+
+                if directives.is_disabled(name, capture.node, code) {
+                    continue;
+                }
+
+                let r#match = LintMatch {
+                    lint_name: name.to_string(),
+                    message: message.clone(),
+                    severity: *severity,
+                    range: Range::from(capture.node.range()),
+                };
+                let () = results.push(r#match);
+            }
+        }
+
+        if query_cursor.did_exceed_match_limit() {
+            warn!("query exceeded maximum number of in-progress captures");
+        }
+        Ok(results)
     }
 }
 
 
-fn lint_impl(tree: &Tree, code: &[u8], lint_src: &str, lint_name: &str) -> Result<Vec<LintMatch>> {
-    let query =
-        Query::new(&LANGUAGE.into(), lint_src).with_context(|| "failed to compile lint query")?;
-    let mut query_cursor = QueryCursor::new();
-    let mut results = Vec::new();
-    let mut matches = query_cursor.matches(&query, tree.root_node(), code);
-    while let Some(m) = matches.next() {
-        for capture in m.captures {
-            if is_lint_disabled(lint_name, capture.node, code) {
-                continue;
-            }
+/// A linter for BPF C code.
+///
+/// A `Linter` compiles the lints it is created with once, upfront, and
+/// can then be used to lint an arbitrary number of inputs without
+/// incurring this cost again.
+pub struct Linter {
+    /// The compiled lints to check for.
+    lints: Vec<Lint>,
+    /// The parser used for parsing source code into a syntax tree.
+    parser: Mutex<Parser>,
+}
 
-            let settings = query.property_settings(m.pattern_index);
-            let setting = settings
-                .iter()
-                .find(|prop| &*prop.key == "message")
-                .with_context(|| format!("{lint_name}: failed to find `message` property"))?;
+impl Linter {
+    /// Create a new `Linter` using the default set of lints.
+    ///
+    /// The default set comprises all built-in lints, except for those
+    /// not [enabled by default][LintMeta::enabled_by_default].
+    pub fn new() -> Result<Self> {
+        let lints = lints::LINTS
+            .iter()
+            .filter(|lint| lint.enabled)
+            .map(Lint::from_builtin)
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_lints(lints)
+    }
 
-            let r#match = LintMatch {
-                lint_name: lint_name.to_string(),
-                message: setting
-                    .value
-                    .as_ref()
-                    .with_context(|| format!("{lint_name}: `message` property has no value set"))?
-                    .to_string(),
-                range: Range::from(capture.node.range()),
-            };
-            let () = results.push(r#match);
+    /// Create a new `Linter` using the provided subset of built-in
+    /// lints.
+    ///
+    /// - `names` are the names of the lints to use, as reported by
+    ///   [`builtin_lints`]
+    ///
+    /// An error is reported if a name does not refer to a built-in lint.
+    pub fn with_lints<I, S>(names: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let lints = names
+            .into_iter()
+            .map(|name| {
+                let name = name.as_ref();
+                let lint = lints::LINTS
+                    .iter()
+                    .find(|lint| lint.name == name)
+                    .ok_or_else(|| anyhow!("lint `{name}` is unknown"))?;
+                Lint::from_builtin(lint)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_lints(lints)
+    }
+
+    /// Create a new `Linter` using all built-in lints, including those
+    /// not enabled by default.
+    pub(crate) fn with_all_lints() -> Result<Self> {
+        let lints = lints::LINTS
+            .iter()
+            .map(Lint::from_builtin)
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_lints(lints)
+    }
+
+    fn from_lints(lints: Vec<Lint>) -> Result<Self> {
+        let slf = Self {
+            lints,
+            parser: Mutex::new(parser()?),
+        };
+        Ok(slf)
+    }
+
+    /// Add a lint to the set of lints to check for.
+    ///
+    /// An error is reported if the `Linter` already uses a lint with the
+    /// same name.
+    pub fn add_lint(&mut self, lint: Lint) -> Result<()> {
+        let name = &lint.meta.name;
+        if self.lints.iter().any(|other| other.meta.name == *name) {
+            return Err(anyhow!("lint `{name}` is already present"))
         }
+        let () = self.lints.push(lint);
+        Ok(())
+    }
+
+    /// Remove the lint `name` from the set of lints to check for.
+    ///
+    /// An error is reported if the `Linter` does not use a lint with
+    /// this name.
+    pub fn remove_lint(&mut self, name: &str) -> Result<Lint> {
+        let idx = self
+            .lints
+            .iter()
+            .position(|lint| lint.meta.name == name)
+            .ok_or_else(|| anyhow!("lint `{name}` is unknown"))?;
+        Ok(self.lints.remove(idx))
     }
 
-    if query_cursor.did_exceed_match_limit() {
-        warn!("query exceeded maximum number of in-progress captures");
+    /// Retain only the lints for which `f` returns `true`, removing all
+    /// others from the set of lints to check for.
+    pub fn retain_lints<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&LintMeta) -> bool,
+    {
+        let () = self.lints.retain(|lint| f(lint.meta()));
+    }
+
+    /// Retrieve meta data about the lints this `Linter` checks for.
+    pub fn lints(&self) -> impl ExactSizeIterator<Item = &LintMeta> + DoubleEndedIterator {
+        self.lints.iter().map(Lint::meta)
+    }
+
+    /// Overwrite the severity with which matches of the lint `name`
+    /// are reported.
+    ///
+    /// An error is reported if the `Linter` does not use a lint with
+    /// this name.
+    pub fn set_severity(&mut self, name: &str, severity: Severity) -> Result<()> {
+        let lint = self
+            .lints
+            .iter_mut()
+            .find(|lint| lint.meta.name == name)
+            .ok_or_else(|| anyhow!("lint `{name}` is unknown"))?;
+        lint.severity = severity;
+        Ok(())
+    }
+
+    /// Lint code using the lints this `Linter` was created with.
+    ///
+    /// Matches are reported in source code order.
+    ///
+    /// - `code` is the source code in question, for example as read
+    ///   from a file
+    pub fn lint(&self, code: &[u8]) -> Result<Vec<LintMatch>> {
+        let tree = {
+            let mut parser = self
+                .parser
+                .lock()
+                .map_err(|_err| anyhow!("parser lock is poisoned"))?;
+            parser
+                .parse(code, None)
+                .context("failed to provided source code")?
+        };
+
+        let mut results = self.lint_tree(&tree, code, None)?;
+        // Sort results to ensure more consistent reporting with ascending
+        // lines.
+        let () = sort_matches(&mut results);
+        Ok(results)
+    }
+
+    /// Lint an already parsed syntax tree, optionally only reporting
+    /// matches intersecting the byte range `bytes`.
+    ///
+    /// Matches are reported in no particular order.
+    pub(crate) fn lint_tree(
+        &self,
+        tree: &Tree,
+        code: &[u8],
+        bytes: Option<ops::Range<usize>>,
+    ) -> Result<Vec<LintMatch>> {
+        let directives = Directives::new(tree, code);
+        let mut results = Vec::new();
+        for lint in &self.lints {
+            let matches = lint.matches(tree, code, bytes.clone(), &directives)?;
+            let () = results.extend(matches);
+        }
+        Ok(results)
     }
-    Ok(results)
 }
 
-fn lint_multi(code: &[u8], lints: &[(&str, &str)]) -> Result<Vec<LintMatch>> {
-    let mut parser = Parser::new();
-    let () = parser
-        .set_language(&LANGUAGE.into())
-        .context("failed to load C parser")?;
-    let tree = parser
-        .parse(code, None)
-        .context("failed to provided source code")?;
-    let mut results = Vec::new();
-    for (lint_name, lint_src) in lints {
-        let matches = lint_impl(&tree, code, lint_src, lint_name)?;
-        let () = results.extend(matches);
-    }
-
-    // Sort results to ensure more consistent reporting with ascending
-    // lines.
-    let () = results.sort_by(|match1, match2| {
-        // NB: We use an ad-hoc comparison rather than a proper
-        // `PartialOrd` impl for `Range`, because the latter is a bit
-        // harder to do correctly.
-        match1
-            .range
-            .start_point
-            .cmp(&match2.range.start_point)
-            .then_with(|| match1.range.end_point.cmp(&match2.range.end_point))
-    });
-    Ok(results)
+impl Debug for Linter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { lints, parser: _ } = self;
+
+        f.debug_struct("Linter")
+            .field("lints", &lints)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Lint code using the default set of lints.
@@ -194,8 +670,27 @@ fn lint_multi(code: &[u8], lints: &[(&str, &str)]) -> Result<Vec<LintMatch>> {
 ///
 /// - `code` is the source code in question, for example as read from a
 ///   file
+///
+/// This function is a convenience wrapper around [`Linter::lint`]. When
+/// linting more than a single input, prefer creating a [`Linter`]
+/// once and reusing it, as doing so avoids recompiling all lints.
 pub fn lint(code: &[u8]) -> Result<Vec<LintMatch>> {
-    lint_multi(code, &lints::LINTS)
+    Linter::new()?.lint(code)
+}
+
+/// Check whether `code` contains syntax errors, i.e., parts that could
+/// not be parsed cleanly.
+///
+/// Lints may not cover such parts. They are reported by the
+/// `syntax-error` lint.
+///
+/// - `code` is the source code in question, for example as read from a
+///   file
+pub fn has_syntax_errors(code: &[u8]) -> Result<bool> {
+    let tree = parser()?
+        .parse(code, None)
+        .context("failed to parse source code")?;
+    Ok(tree.root_node().has_error())
 }
 
 
@@ -203,11 +698,32 @@ pub fn lint(code: &[u8]) -> Result<Vec<LintMatch>> {
 mod tests {
     use super::*;
 
+    use std::fs::write;
+
     use indoc::indoc;
 
+    use tempfile::tempdir;
+
     use crate::Point;
 
 
+    /// Create a `Linter` using the provided set of lints, given in
+    /// source form.
+    fn linter(lints: &[(&str, &str)]) -> Result<Linter> {
+        let lints = lints
+            .iter()
+            .map(|(name, src)| Lint::from_source(name, src))
+            .collect::<Result<Vec<_>>>()?;
+        Linter::from_lints(lints)
+    }
+
+    /// Lint `code` using the provided set of lints, given in source
+    /// form.
+    fn lint_multi(code: &[u8], lints: &[(&str, &str)]) -> Result<Vec<LintMatch>> {
+        linter(lints)?.lint(code)
+    }
+
+
     static LINT_FOO: (&str, &str) = (
         "foo",
         r#"
@@ -243,20 +759,202 @@ mod tests {
     /// exhibit the expected set of properties.
     #[test]
     fn validate_lint_queries() {
-        for (name, code) in lints::LINTS {
-            let query = Query::new(&LANGUAGE.into(), code).unwrap();
-            assert_eq!(
-                query.pattern_count(),
-                1,
-                "lint `{name}` has too many pattern matches: only single one is supported currently"
-            );
+        for BuiltinLint { name, src, .. } in lints::LINTS {
+            let query = Query::new(&LANGUAGE.into(), src).unwrap();
+            for idx in 0..query.pattern_count() {
+                let settings = query.property_settings(idx);
+                assert!(
+                    settings.iter().any(|prop| &*prop.key == "message"),
+                    "`message` property is missing for pattern {idx} of lint `{name}`"
+                );
+            }
+        }
+    }
 
-            let settings = query.property_settings(0);
-            assert!(
-                settings.iter().any(|prop| &*prop.key == "message"),
-                "`message` property is missing for lint `{name}`"
-            );
+    /// Check that a lint can consist of multiple patterns, each with
+    /// its own message.
+    #[test]
+    fn multi_pattern_lint() {
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifier) @function (#eq? @function "foo")
+              (#set! "message" "foo is bad")
+          )
+          (call_expression
+              function: (identifier) @function (#eq? @function "bar")
+              (#set! "message" "bar is worse")
+          )
+        "# };
+        let code = indoc! { r#"
+          bar();
+          foo();
+          baz();
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[("foobar", lint)]).unwrap();
+        assert_eq!(matches.len(), 2, "{matches:?}");
+        assert_eq!(matches[0].lint_name, "foobar");
+        assert_eq!(matches[0].message, "bar is worse");
+        assert_eq!(matches[1].lint_name, "foobar");
+        assert_eq!(matches[1].message, "foo is bad");
+    }
+
+    /// Check that only the `@report` capture is reported for patterns
+    /// designating one.
+    #[test]
+    fn report_capture() {
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifier) @function (#eq? @function "foo")
+              arguments: (argument_list (identifier) @arg (#eq? @arg "bar"))
+              (#set! "message" "foo(bar) is bad")
+          ) @report
+        "# };
+        let code = indoc! { r#"
+          foo(bar);
+          foo(baz);
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[("foobar", lint)]).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(&code[matches[0].range.bytes.clone()], "foo(bar)");
+    }
+
+    /// Check that patterns without a `@report` capture report all their
+    /// captures, even if other patterns of the same lint designate one.
+    #[test]
+    fn report_capture_multi_pattern() {
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifier) @function (#eq? @function "foo")
+              (#set! "message" "foo is bad")
+          ) @report
+
+          (call_expression
+              function: (identifier) @function (#eq? @function "bar")
+              arguments: (argument_list (identifier) @arg)
+              (#set! "message" "bar is bad")
+          )
+        "# };
+        let code = indoc! { r#"
+          foo();
+          bar(baz);
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[("foobar", lint)]).unwrap();
+        assert_eq!(matches.len(), 3, "{matches:?}");
+        assert_eq!(&code[matches[0].range.bytes.clone()], "foo()");
+        assert_eq!(&code[matches[1].range.bytes.clone()], "bar");
+        assert_eq!(&code[matches[2].range.bytes.clone()], "baz");
+    }
+
+    /// Check that the `severity` property of a lint is honored.
+    #[test]
+    fn severity_property() {
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifier) @function (#eq? @function "foo")
+              (#set! "message" "foo is bad")
+              (#set! "severity" "error")
+          )
+          (call_expression
+              function: (identifier) @function (#eq? @function "bar")
+              (#set! "message" "bar is bad")
+          )
+        "# };
+        let code = indoc! { r#"
+          foo();
+          bar();
+          baz();
+        "# };
+        let mut linter = linter(&[("foobar", lint), LINT_FOO]).unwrap();
+        assert_eq!(linter.lints[0].meta.default_severity, Severity::Error);
+        assert_eq!(linter.lints[1].meta.default_severity, Severity::Warning);
+
+        let matches = linter.lint(code.as_bytes()).unwrap();
+        assert_eq!(matches.len(), 3, "{matches:?}");
+        assert_eq!(matches[0].severity, Severity::Error);
+        assert_eq!(matches[1].severity, Severity::Warning);
+        assert_eq!(matches[2].severity, Severity::Error);
+
+        let () = linter.set_severity("foobar", Severity::Hint).unwrap();
+        let matches = linter.lint(code.as_bytes()).unwrap();
+        assert!(
+            matches
+                .iter()
+                .filter(|m| m.lint_name == "foobar")
+                .all(|m| m.severity == Severity::Hint),
+            "{matches:?}"
+        );
+
+        let err = linter.set_severity("baz", Severity::Hint).unwrap_err();
+        assert_eq!(err.to_string(), "lint `baz` is unknown");
+    }
+
+    /// Check that invalid or conflicting `severity` properties are
+    /// flagged.
+    #[test]
+    fn invalid_severity_property() {
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifier) @function (#eq? @function "foo")
+              (#set! "message" "foo is bad")
+              (#set! "severity" "fatal")
+          )
+        "# };
+        let err = lint_multi(b"", &[("foo", lint)]).unwrap_err();
+        assert_eq!(err.to_string(), "foo: `severity` property is invalid");
+
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifier) @function (#eq? @function "foo")
+              (#set! "message" "foo is bad")
+              (#set! "severity" "error")
+          )
+          (call_expression
+              function: (identifier) @function (#eq? @function "bar")
+              (#set! "message" "bar is bad")
+              (#set! "severity" "hint")
+          )
+        "# };
+        let err = lint_multi(b"", &[("foobar", lint)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "foobar: patterns declare conflicting `severity` properties"
+        );
+    }
+
+    /// Check that we can parse and print severities.
+    #[test]
+    fn severity_parsing() {
+        for severity in [
+            Severity::Hint,
+            Severity::Info,
+            Severity::Warning,
+            Severity::Error,
+        ] {
+            assert_eq!(Severity::from_str(&severity.to_string()).unwrap(), severity);
         }
+        assert!(Severity::from_str("Warning").is_err());
+        assert!(Severity::Hint < Severity::Error);
+    }
+
+    /// Check that a missing `message` property is flagged even if only
+    /// a single one of multiple patterns lacks it.
+    #[test]
+    fn missing_message_property_multi_pattern() {
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifier) @function (#eq? @function "foo")
+              (#set! "message" "foo is bad")
+          )
+          (call_expression
+              function: (identifier) @function (#eq? @function "bar")
+          )
+        "# };
+        let err = lint_multi(b"", &[("foobar", lint)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "foobar: failed to find `message` property",
+            "{err}"
+        );
     }
 
     /// Check that some basic linting works as expected.
@@ -280,9 +978,11 @@ mod tests {
         let LintMatch {
             lint_name,
             message,
+            severity,
             range,
         } = &matches[0];
         assert_eq!(lint_name, "probe-read");
+        assert_eq!(*severity, Severity::Warning);
         assert!(
             message.starts_with("bpf_probe_read() is deprecated"),
             "{message}"
@@ -292,6 +992,154 @@ mod tests {
         assert_eq!(range.end_point, Point { row: 6, col: 18 });
     }
 
+    /// Check that a `Linter` can be reused for linting multiple inputs.
+    #[test]
+    fn linter_reuse() {
+        let linter = linter(&[LINT_FOO]).unwrap();
+        let matches = linter.lint(b"foo();").unwrap();
+        assert_eq!(matches.len(), 1);
+
+        let matches = linter.lint(b"bar();\nfoo();\nfoo();").unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].range.start_point, Point { row: 1, col: 0 });
+        assert_eq!(matches[1].range.start_point, Point { row: 2, col: 0 });
+    }
+
+    /// Check that we can create a `Linter` using a subset of built-in
+    /// lints and that unknown lint names are rejected.
+    #[test]
+    fn linter_builtin_subset() {
+        let linter = Linter::with_lints(["probe-read"]).unwrap();
+        assert_eq!(linter.lints.len(), 1);
+        assert_eq!(linter.lints[0].meta.name, "probe-read");
+
+        let err = Linter::with_lints(["probe-read", "does-not-exist"]).unwrap_err();
+        assert_eq!(err.to_string(), "lint `does-not-exist` is unknown");
+    }
+
+    /// Check that the meta data of built-in lints, as generated at build
+    /// time, is consistent with their compiled form.
+    #[test]
+    fn builtin_lint_meta_consistency() {
+        for lint in &lints::LINTS {
+            let compiled = Lint::from_source(lint.name, lint.src).unwrap();
+            assert_eq!(
+                compiled.meta.default_severity, lint.severity,
+                "{}",
+                lint.name
+            );
+            assert_eq!(
+                Lint::from_builtin(lint).unwrap().severity,
+                lint.severity,
+                "{}",
+                lint.name
+            );
+        }
+        assert_eq!(builtin_lints().len(), lints::LINTS.len());
+    }
+
+    /// Check that lints not enabled by default are excluded from the
+    /// default set, but can still be used explicitly.
+    #[test]
+    fn linter_default_set() {
+        let linter = Linter::new().unwrap();
+        assert!(linter.lints().all(|lint| lint.enabled_by_default));
+        assert!(linter.lints().all(|lint| lint.name != "syntax-error"));
+
+        let meta = builtin_lints()
+            .find(|lint| lint.name == "syntax-error")
+            .unwrap();
+        assert!(!meta.enabled_by_default);
+
+        let linter = Linter::with_lints(["syntax-error"]).unwrap();
+        let matches = linter.lint(b"int x = ;").unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert!(lint(b"int x = ;").unwrap().is_empty());
+    }
+
+    /// Check that we can add custom lints to a `Linter` and that they
+    /// are subject to the regular disabling logic.
+    #[test]
+    fn custom_lint() {
+        let mut linter = linter(&[]).unwrap();
+        let lint = Lint::from_source(LINT_FOO.0, LINT_FOO.1).unwrap();
+        assert_eq!(lint.meta().name, "foo");
+        let () = linter.add_lint(lint).unwrap();
+
+        let lint = Lint::from_source(LINT_FOO.0, LINT_FOO.1).unwrap();
+        let err = linter.add_lint(lint).unwrap_err();
+        assert_eq!(err.to_string(), "lint `foo` is already present");
+        assert_eq!(
+            linter.lints().map(|meta| &meta.name).collect::<Vec<_>>(),
+            ["foo"]
+        );
+
+        let lint = linter.remove_lint("foo").unwrap();
+        assert_eq!(linter.lints().len(), 0);
+        let err = linter.remove_lint("foo").unwrap_err();
+        assert_eq!(err.to_string(), "lint `foo` is unknown");
+        let () = linter.add_lint(lint).unwrap();
+
+        let code = indoc! { r#"
+          foo();
+          /* bpflint: disable=foo */
+          foo();
+        "# };
+        let matches = linter.lint(code.as_bytes()).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(matches[0].lint_name, "foo");
+    }
+
+    /// Check that we report errors in custom lint queries properly.
+    #[test]
+    fn custom_lint_compile_error() {
+        let lint = indoc! { r#"
+          (call_expression
+              function: (identifierr) @function
+              (#set! "message" "foo")
+          )
+        "# };
+        let err = Lint::from_source("broken", lint).unwrap_err();
+        assert_eq!(err.to_string(), "broken: failed to compile lint query");
+        assert_eq!(
+            format!("{err:#}"),
+            "broken: failed to compile lint query: Query error at 2:16. Invalid node type identifierr"
+        );
+    }
+
+    /// Check that we can load a lint from a file.
+    #[test]
+    fn custom_lint_from_file() {
+        let dir = env!("CARGO_MANIFEST_DIR");
+        let path = Path::new(dir).join("lints").join("probe-read.scm");
+        let lint = Lint::from_file(&path).unwrap();
+        assert_eq!(lint.meta().name, "probe-read");
+
+        let path = Path::new(dir).join("lints").join("does-not-exist.scm");
+        let err = Lint::from_file(&path).unwrap_err();
+        assert!(err.to_string().starts_with("failed to read lint"), "{err}");
+    }
+
+    /// Check that we can load lints from a directory.
+    #[test]
+    fn custom_lint_dir_loading() {
+        let dir = tempdir().unwrap();
+        let () = write(dir.path().join("foo.scm"), LINT_FOO.1).unwrap();
+        let () = write(dir.path().join("bar.scm"), LINT_FOO.1).unwrap();
+        let () = write(dir.path().join("README.md"), "not a lint").unwrap();
+
+        let lints = Lint::load_dir(dir.path()).unwrap();
+        let names = lints
+            .iter()
+            .map(|lint| lint.meta().name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["bar", "foo"]);
+
+        let () = write(dir.path().join("baz.scm"), "(call_expression").unwrap();
+        let err = Lint::load_dir(dir.path()).unwrap_err();
+        assert!(err.to_string().starts_with("failed to load lint"), "{err}");
+    }
+
     /// Check that reported matches are sorted by line number.
     #[test]
     fn sorted_match_reporting() {
@@ -350,6 +1198,105 @@ mod tests {
         assert_eq!(matches.len(), 0, "{matches:?}");
     }
 
+    /// Check that we can disable multiple lints with a single directive.
+    #[test]
+    fn lint_disabling_list() {
+        let code = indoc! { r#"
+          /* bpflint: disable=bar, foo */
+          foo();
+          // bpflint: disable=bar,baz
+          foo();
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[LINT_FOO]).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(matches[0].range.start_point.row, 3);
+    }
+
+    /// Check that we can disable lints for the next line.
+    #[test]
+    fn lint_disabling_next_line() {
+        let code = indoc! { r#"
+          void test_fn(void) {
+              // bpflint: disable-next-line=foo
+              bar(); foo();
+              foo();
+              /* bpflint: disable-next-line=all */
+
+              foo();
+          }
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[LINT_FOO]).unwrap();
+        assert_eq!(matches.len(), 2, "{matches:?}");
+        assert_eq!(matches[0].range.start_point.row, 3);
+        assert_eq!(matches[1].range.start_point.row, 6);
+    }
+
+    /// Check that trailing directives disable lints for the line they
+    /// are on.
+    #[test]
+    fn lint_disabling_trailing() {
+        let code = indoc! { r#"
+          void test_fn(void) {
+              foo(); // bpflint: disable=foo
+              foo();
+              foo(
+                1); /* bpflint: disable=foo */ foo();
+              bar(); /* bpflint: disable=foo */
+              foo();
+          }
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[LINT_FOO]).unwrap();
+        assert_eq!(matches.len(), 3, "{matches:?}");
+        assert_eq!(matches[0].range.start_point.row, 2);
+        assert_eq!(matches[1].range.start_point.row, 3);
+        assert_eq!(matches[2].range.start_point.row, 6);
+    }
+
+    /// Check that we can disable lints for an entire file.
+    #[test]
+    fn lint_disabling_file() {
+        let code = indoc! { r#"
+          foo();
+
+          void test_fn(void) {
+              foo();
+          }
+          /* bpflint: disable-file=foo */
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[LINT_FOO]).unwrap();
+        assert_eq!(matches.len(), 0, "{matches:?}");
+
+        let code = indoc! { r#"
+          // bpflint: disable-file=bar
+          foo();
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[LINT_FOO]).unwrap();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+    }
+
+    /// Check that we can disable lints for a region of code.
+    #[test]
+    fn lint_disabling_region() {
+        let code = indoc! { r#"
+          foo();
+          /* bpflint: disable-start=foo,bar */
+          foo();
+          void test_fn(void) {
+              foo();
+              // bpflint: disable-end=foo
+              foo();
+              // bpflint: disable-start=all
+              foo();
+          }
+          // bpflint: disable-end=foo
+          foo();
+        "# };
+        let matches = lint_multi(code.as_bytes(), &[LINT_FOO]).unwrap();
+        assert_eq!(matches.len(), 2, "{matches:?}");
+        assert_eq!(matches[0].range.start_point.row, 0);
+        assert_eq!(matches[1].range.start_point.row, 6);
+    }
+
     /// Check that erroneous disabling syntax is not accidentally recognized.
     #[test]
     fn lint_invalid_disabling() {
@@ -0,0 +1,158 @@
+use std::borrow::Cow;
+use std::io;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::LintMatch;
+use crate::Severity;
+
+
+/// Escape `s` for usage as the data part of a workflow command.
+fn escape_data(s: &str) -> Cow<'_, str> {
+    if s.contains(['%', '\r', '\n']) {
+        Cow::Owned(
+            s.replace('%', "%25")
+                .replace('\r', "%0D")
+                .replace('\n', "%0A"),
+        )
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// Escape `s` for usage as a property value of a workflow command.
+fn escape_property(s: &str) -> Cow<'_, str> {
+    let s = escape_data(s);
+    if s.contains([':', ',']) {
+        Cow::Owned(s.replace(':', "%3A").replace(',', "%2C"))
+    } else {
+        s
+    }
+}
+
+
+/// Report a lint match as a GitHub Actions workflow command, causing
+/// it to be shown as an annotation of the affected code.
+///
+/// Matches with [`Severity::Hint`] and [`Severity::Info`] are reported
+/// as notices.
+///
+/// - `match` is the match to create a report for
+/// - `path` should be the path to the file in which the match was
+///   found, relative to the repository root
+/// - `writer` is a reference to a [`io::Write`] to which to write the
+///   report
+///
+/// # Example
+/// ```text
+/// ::warning file=example.bpf.c,line=43,col=25,endLine=43,endColumn=38,title=[probe-read]::bpf_probe_read() is deprecated
+/// ```
+pub fn report_github(r#match: &LintMatch, path: &Path, writer: &mut dyn io::Write) -> Result<()> {
+    let LintMatch {
+        lint_name,
+        message,
+        severity,
+        range,
+    } = r#match;
+
+    let command = match severity {
+        Severity::Hint | Severity::Info => "notice",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    };
+    let path = path.to_string_lossy();
+    write!(
+        writer,
+        "::{command} file={},line={}",
+        escape_property(&path),
+        range.start_point.row + 1
+    )?;
+    // Workflow commands use 1-based lines and columns, with the end
+    // column being inclusive. Empty ranges mark an insertion point,
+    // which we annotate as a single column.
+    if !range.is_file_level() {
+        let end_col = if range.bytes.is_empty() {
+            range.start_point.col + 1
+        } else {
+            range.end_point.col
+        };
+        write!(
+            writer,
+            ",col={},endLine={},endColumn={end_col}",
+            range.start_point.col + 1,
+            range.end_point.row + 1,
+        )?;
+    }
+    writeln!(
+        writer,
+        ",title={}::{}",
+        escape_property(&format!("[{lint_name}]")),
+        escape_data(message)
+    )?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::Point;
+    use crate::Range;
+
+
+    /// Check that we report lint matches as GitHub workflow commands.
+    #[test]
+    fn github_reporting() {
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "bpf_probe_read() is deprecated".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: 1186..1200,
+                start_point: Point { row: 42, col: 24 },
+                end_point: Point { row: 42, col: 38 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_github(&m, Path::new("example.bpf.c"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = "::warning file=example.bpf.c,line=43,col=25,endLine=43,endColumn=38,title=[probe-read]::bpf_probe_read() is deprecated\n";
+        assert_eq!(report, expected);
+
+        let m = LintMatch {
+            lint_name: "bogus-file-extension".to_string(),
+            message: "100% wrong\nextension".to_string(),
+            severity: Severity::Hint,
+            range: Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_github(&m, Path::new("a,b:c.c"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = "::notice file=a%2Cb%3Ac.c,line=1,title=[bogus-file-extension]::100%25 wrong%0Aextension\n";
+        assert_eq!(report, expected);
+
+        let m = LintMatch {
+            lint_name: "syntax-error".to_string(),
+            message: "missing code".to_string(),
+            severity: Severity::Info,
+            range: Range {
+                bytes: 40..40,
+                start_point: Point { row: 2, col: 12 },
+                end_point: Point { row: 2, col: 12 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_github(&m, Path::new("example.bpf.c"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = "::notice file=example.bpf.c,line=3,col=13,endLine=3,endColumn=13,title=[syntax-error]::missing code\n";
+        assert_eq!(report, expected);
+    }
+}
@@ -0,0 +1,232 @@
+use std::borrow::Cow;
+use std::io;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::LintMatch;
+use crate::Point;
+
+
+/// Write `s` as a JSON string, including surrounding quotes.
+fn write_str(writer: &mut dyn io::Write, s: &str) -> io::Result<()> {
+    write!(writer, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(writer, "\\\"")?,
+            '\\' => write!(writer, "\\\\")?,
+            '\n' => write!(writer, "\\n")?,
+            '\r' => write!(writer, "\\r")?,
+            '\t' => write!(writer, "\\t")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", u32::from(c))?,
+            c => write!(writer, "{c}")?,
+        }
+    }
+    write!(writer, "\"")
+}
+
+
+/// A JSON value, as used for assembling reports.
+#[derive(Clone, Debug)]
+pub(super) enum Value<'v> {
+    /// A number.
+    Number(usize),
+    /// A string.
+    String(Cow<'v, str>),
+    /// An array of values.
+    Array(Vec<Value<'v>>),
+    /// An object, with members being written in the given order.
+    Object(Vec<(&'static str, Value<'v>)>),
+}
+
+impl Value<'_> {
+    /// Write the value in its compact JSON representation.
+    pub(super) fn write(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        match self {
+            Self::Number(number) => write!(writer, "{number}"),
+            Self::String(s) => write_str(writer, s),
+            Self::Array(values) => {
+                write!(writer, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(writer, ",")?;
+                    }
+                    let () = value.write(writer)?;
+                }
+                write!(writer, "]")
+            },
+            Self::Object(members) => {
+                write!(writer, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(writer, ",")?;
+                    }
+                    let () = write_str(writer, key)?;
+                    write!(writer, ":")?;
+                    let () = value.write(writer)?;
+                }
+                write!(writer, "}}")
+            },
+        }
+    }
+}
+
+impl From<usize> for Value<'_> {
+    fn from(other: usize) -> Self {
+        Self::Number(other)
+    }
+}
+
+impl<'v> From<&'v str> for Value<'v> {
+    fn from(other: &'v str) -> Self {
+        Self::String(Cow::Borrowed(other))
+    }
+}
+
+impl From<String> for Value<'_> {
+    fn from(other: String) -> Self {
+        Self::String(Cow::Owned(other))
+    }
+}
+
+impl<'v> From<Vec<Value<'v>>> for Value<'v> {
+    fn from(other: Vec<Value<'v>>) -> Self {
+        Self::Array(other)
+    }
+}
+
+
+fn point(point: &Point) -> Value<'static> {
+    let Point { row, col } = point;
+    Value::Object(vec![("row", (*row).into()), ("col", (*col).into())])
+}
+
+
+/// Report a lint match as a single line JSON object.
+///
+/// The object has the same layout as produced by the `Serialize`
+/// implementation of [`LintMatch`] (available with the `serde`
+/// feature), with an additional `path` member.
+///
+/// - `match` is the match to create a report for
+/// - `path` should be the path to the file in which the match was found
+/// - `writer` is a reference to a [`io::Write`] to which to write the
+///   report
+///
+/// # Example
+/// ```json
+/// {"lint_name":"probe-read","message":"bpf_probe_read() is deprecated","severity":"warning","path":"example.bpf.c","range":{"bytes":{"start":1186,"end":1200},"start_point":{"row":42,"col":24},"end_point":{"row":42,"col":38}}}
+/// ```
+pub fn report_json(r#match: &LintMatch, path: &Path, writer: &mut dyn io::Write) -> Result<()> {
+    let LintMatch {
+        lint_name,
+        message,
+        severity,
+        range,
+    } = r#match;
+
+    let bytes = Value::Object(vec![
+        ("start", range.bytes.start.into()),
+        ("end", range.bytes.end.into()),
+    ]);
+    let range = Value::Object(vec![
+        ("bytes", bytes),
+        ("start_point", point(&range.start_point)),
+        ("end_point", point(&range.end_point)),
+    ]);
+    let json = Value::Object(vec![
+        ("lint_name", lint_name.as_str().into()),
+        ("message", message.as_str().into()),
+        ("severity", severity.to_string().into()),
+        ("path", path.to_string_lossy().into_owned().into()),
+        ("range", range),
+    ]);
+    let () = json.write(writer)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::Range;
+    use crate::Severity;
+
+
+    /// Check that we escape strings as required by JSON.
+    #[test]
+    fn string_escaping() {
+        let mut json = Vec::new();
+        let () = write_str(&mut json, "a \"quoted\"\\path\n\twith\u{1}ümlaut").unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(json, r#""a \"quoted\"\\path\n\twith\u0001ümlaut""#);
+    }
+
+    /// Check that we write nested JSON values as expected.
+    #[test]
+    fn value_writing() {
+        let value = Value::Object(vec![
+            (
+                "a",
+                Value::Array(vec![1.into(), "two".into(), Value::Array(Vec::new())]),
+            ),
+            ("b\"", Value::Object(Vec::new())),
+            ("c", Value::Object(vec![("d", String::from("e").into())])),
+        ]);
+        let mut json = Vec::new();
+        let () = value.write(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(json, r#"{"a":[1,"two",[]],"b\"":{},"c":{"d":"e"}}"#);
+    }
+
+    /// Check that we report a lint match in the expected JSON format.
+    #[test]
+    fn json_reporting() {
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "use \"bpf_probe_read_kernel\"".to_string(),
+            severity: Severity::Error,
+            range: Range {
+                bytes: 12..26,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 1, col: 18 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_json(&m, Path::new("src/foo.bpf.c"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = r#"{"lint_name":"probe-read","message":"use \"bpf_probe_read_kernel\"","severity":"error","path":"src/foo.bpf.c","range":{"bytes":{"start":12,"end":26},"start_point":{"row":1,"col":4},"end_point":{"row":1,"col":18}}}
+"#;
+        assert_eq!(report, expected);
+    }
+
+    /// Check that our JSON report is consistent with the `Serialize`
+    /// implementation of `LintMatch`.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_consistency() {
+        use serde_json::Value;
+        use serde_json::to_value;
+
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "bpf_probe_read() is deprecated".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: 0..14,
+                start_point: Point { row: 0, col: 0 },
+                end_point: Point { row: 0, col: 14 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_json(&m, Path::new("foo.bpf.c"), &mut report).unwrap();
+        let mut report = serde_json::from_slice::<Value>(&report).unwrap();
+        let path = report.as_object_mut().unwrap().remove("path").unwrap();
+        assert_eq!(path, "foo.bpf.c");
+        assert_eq!(report, to_value(&m).unwrap());
+    }
+}
@@ -0,0 +1,288 @@
+use std::io;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::LintMatch;
+use crate::LintMeta;
+use crate::Severity;
+
+use super::json::Value;
+
+
+/// The URI of the SARIF 2.1.0 JSON schema.
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+
+/// Map a [`Severity`] to a SARIF result level.
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Hint | Severity::Info => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+/// Create a SARIF message object with the given text.
+fn message(text: &str) -> Value<'_> {
+    Value::Object(vec![("text", text.into())])
+}
+
+/// Convert `path` into a URI reference, percent-encoding all
+/// characters other than unreserved ones and path separators.
+fn uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            let () = uri.push(char::from(byte));
+        } else {
+            let () = uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+/// Create the SARIF `reportingDescriptor` describing `lint`.
+fn rule(lint: &LintMeta) -> Value<'_> {
+    let LintMeta {
+        name,
+        default_severity,
+        summary,
+        rationale,
+        references,
+        ..
+    } = lint;
+
+    let mut rule = vec![
+        ("id", name.as_str().into()),
+        ("shortDescription", message(summary)),
+        ("fullDescription", message(rationale)),
+    ];
+    if let Some(uri) = references
+        .iter()
+        .find(|reference| reference.starts_with("https://") || reference.starts_with("http://"))
+    {
+        let () = rule.push(("helpUri", uri.as_str().into()));
+    }
+    let () = rule.push((
+        "defaultConfiguration",
+        Value::Object(vec![("level", level(*default_severity).into())]),
+    ));
+    Value::Object(rule)
+}
+
+/// Create the SARIF `result` object for `match`, found in `code` of the
+/// file at `path`.
+fn result<'m>(lints: &[LintMeta], path: &Path, code: &[u8], r#match: &'m LintMatch) -> Value<'m> {
+    let LintMatch {
+        lint_name,
+        message: text,
+        severity,
+        range,
+    } = r#match;
+
+    // SARIF regions are 1-based, with the end column being exclusive.
+    // Columns are measured in UTF-16 code units by default.
+    let (start, end) = range.utf16_points(code);
+    let region = Value::Object(vec![
+        ("startLine", (start.row + 1).into()),
+        ("startColumn", (start.col + 1).into()),
+        ("endLine", (end.row + 1).into()),
+        ("endColumn", (end.col + 1).into()),
+    ]);
+    let location = Value::Object(vec![(
+        "physicalLocation",
+        Value::Object(vec![
+            (
+                "artifactLocation",
+                Value::Object(vec![("uri", uri(path).into())]),
+            ),
+            ("region", region),
+        ]),
+    )]);
+
+    let mut result = vec![("ruleId", lint_name.as_str().into())];
+    if let Some(idx) = lints.iter().position(|lint| lint.name == *lint_name) {
+        let () = result.push(("ruleIndex", idx.into()));
+    }
+    let () = result.extend([
+        ("level", level(*severity).into()),
+        ("message", message(text)),
+        ("locations", Value::Array(vec![location])),
+    ]);
+    Value::Object(result)
+}
+
+
+/// Report lint matches in the Static Analysis Results Interchange
+/// Format (SARIF), version 2.1.0.
+///
+/// A single SARIF log containing a single run is produced for all
+/// provided matches.
+///
+/// - `lints` are the lints that were checked for, which are reported as
+///   the tool's rules
+/// - `matches` are the lint matches to report, along with the paths
+///   of the files they were found in and the source code of these
+///   files
+/// - `writer` is a reference to a [`io::Write`] to which to write the
+///   report
+pub fn report_sarif<'m, M>(lints: &[LintMeta], matches: M, writer: &mut dyn io::Write) -> Result<()>
+where
+    M: IntoIterator<Item = (&'m Path, &'m [u8], &'m LintMatch)>,
+{
+    let driver = Value::Object(vec![
+        ("name", "bpflint".into()),
+        ("informationUri", env!("CARGO_PKG_REPOSITORY").into()),
+        ("version", env!("CARGO_PKG_VERSION").into()),
+        ("rules", lints.iter().map(rule).collect::<Vec<_>>().into()),
+    ]);
+    let results = matches
+        .into_iter()
+        .map(|(path, code, r#match)| result(lints, path, code, r#match))
+        .collect::<Vec<_>>();
+    let run = Value::Object(vec![
+        ("tool", Value::Object(vec![("driver", driver)])),
+        ("results", results.into()),
+    ]);
+    let log = Value::Object(vec![
+        ("$schema", SCHEMA.into()),
+        ("version", "2.1.0".into()),
+        ("runs", Value::Array(vec![run])),
+    ]);
+
+    let () = log.write(writer)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use serde_json::Value;
+    use serde_json::json;
+
+    use crate::Point;
+    use crate::Range;
+    use crate::builtin_lints;
+
+
+    /// Check that we produce a single SARIF run for matches from
+    /// multiple files.
+    #[test]
+    fn sarif_reporting() {
+        let lints = builtin_lints()
+            .filter(|lint| lint.name == "probe-read")
+            .collect::<Vec<_>>();
+        let m1 = LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "bpf_probe_read() is deprecated".to_string(),
+            severity: Severity::Error,
+            range: Range {
+                bytes: 12..26,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 1, col: 18 },
+            },
+        };
+        let m2 = LintMatch {
+            lint_name: "bogus-file-extension".to_string(),
+            message: "use \"*.bpf.c\"".to_string(),
+            severity: Severity::Info,
+            range: Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            },
+        };
+        let code = b"int x;\n    bpf_probe_read();\n";
+        let matches = [
+            (Path::new("src/foo.bpf.c"), code.as_slice(), &m1),
+            (Path::new("bar.c"), b"".as_slice(), &m2),
+        ];
+
+        let mut report = Vec::new();
+        let () = report_sarif(&lints, matches, &mut report).unwrap();
+        let report = serde_json::from_slice::<Value>(&report).unwrap();
+
+        assert_eq!(report["version"], "2.1.0");
+        let runs = report["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 1);
+
+        let rules = &runs[0]["tool"]["driver"]["rules"];
+        assert_eq!(rules[0]["id"], "probe-read");
+        assert_eq!(rules[0]["shortDescription"]["text"], lints[0].summary);
+        assert_eq!(rules[0]["defaultConfiguration"]["level"], "warning");
+
+        let results = runs[0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0],
+            json!({
+                "ruleId": "probe-read",
+                "ruleIndex": 0,
+                "level": "error",
+                "message": {"text": "bpf_probe_read() is deprecated"},
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {"uri": "src/foo.bpf.c"},
+                        "region": {
+                            "startLine": 2,
+                            "startColumn": 5,
+                            "endLine": 2,
+                            "endColumn": 19,
+                        },
+                    },
+                }],
+            })
+        );
+        assert_eq!(results[1]["ruleId"], "bogus-file-extension");
+        assert_eq!(results[1].get("ruleIndex"), None);
+        assert_eq!(results[1]["level"], "note");
+        assert_eq!(results[1]["message"]["text"], "use \"*.bpf.c\"");
+    }
+
+    /// Check that columns are reported in UTF-16 code units.
+    #[test]
+    fn sarif_utf16_columns() {
+        // `ä` is two bytes in UTF-8, but a single UTF-16 code unit,
+        // while `😀` is four bytes and two code units.
+        let code = "/* ä😀 */ bpf_probe_read();";
+        let start = code.find("bpf").unwrap();
+        let end = start + "bpf_probe_read".len();
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "bpf_probe_read() is deprecated".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: start..end,
+                start_point: Point { row: 0, col: start },
+                end_point: Point { row: 0, col: end },
+            },
+        };
+        let matches = [(Path::new("foo.bpf.c"), code.as_bytes(), &m)];
+
+        let mut report = Vec::new();
+        let () = report_sarif(&[], matches, &mut report).unwrap();
+        let report = serde_json::from_slice::<Value>(&report).unwrap();
+        let region = &report["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startColumn"], 11);
+        assert_eq!(region["endColumn"], 25);
+    }
+
+    /// Check that we percent-encode paths when converting them to URIs.
+    #[test]
+    fn uri_encoding() {
+        assert_eq!(uri(Path::new("src/foo.bpf.c")), "src/foo.bpf.c");
+        assert_eq!(
+            uri(Path::new("/tmp/my dir/#1.bpf.c")),
+            "/tmp/my%20dir/%231.bpf.c"
+        );
+        assert_eq!(uri(Path::new("100%.bpf.c")), "100%25.bpf.c");
+        assert_eq!(uri(Path::new("ä.bpf.c")), "%C3%A4.bpf.c");
+    }
+}
@@ -0,0 +1,74 @@
+use std::io;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::LintMatch;
+
+
+/// Report a lint match on a single line, in the style of compiler
+/// diagnostics as understood by most editors.
+///
+/// Line and column numbers are 1-based.
+///
+/// - `match` is the match to create a report for
+/// - `path` should be the path to the file in which the match was found
+/// - `writer` is a reference to a [`io::Write`] to which to write the
+///   report
+///
+/// # Example
+/// ```text
+/// example.bpf.c:43:25: warning: [probe-read] bpf_probe_read() is deprecated
+/// ```
+pub fn report_short(r#match: &LintMatch, path: &Path, writer: &mut dyn io::Write) -> Result<()> {
+    let LintMatch {
+        lint_name,
+        message,
+        severity,
+        range,
+    } = r#match;
+
+    writeln!(
+        writer,
+        "{}:{}:{}: {severity}: [{lint_name}] {message}",
+        path.display(),
+        range.start_point.row + 1,
+        range.start_point.col + 1,
+    )?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::Point;
+    use crate::Range;
+    use crate::Severity;
+
+
+    /// Check that we report lint matches on a single line.
+    #[test]
+    fn short_reporting() {
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "bpf_probe_read() is deprecated".to_string(),
+            severity: Severity::Error,
+            range: Range {
+                bytes: 1186..1200,
+                start_point: Point { row: 42, col: 24 },
+                end_point: Point { row: 42, col: 38 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_short(&m, Path::new("example.bpf.c"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert_eq!(
+            report,
+            "example.bpf.c:43:25: error: [probe-read] bpf_probe_read() is deprecated\n"
+        );
+    }
+}
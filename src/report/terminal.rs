@@ -0,0 +1,764 @@
+use std::io;
+use std::path::Path;
+use std::slice::SliceIndex;
+
+use anyhow::Result;
+
+use unicode_width::UnicodeWidthChar as _;
+
+use crate::LintMatch;
+use crate::Range;
+use crate::Severity;
+
+
+/// The number of columns a tab character is expanded to.
+const TAB_WIDTH: usize = 4;
+/// The maximum number of lines of a multi-line match that we display
+/// in full.
+const MULTI_LINE_MAX_ROWS: usize = 6;
+/// The number of leading lines displayed for longer matches.
+const MULTI_LINE_HEAD_ROWS: usize = 3;
+/// The number of trailing lines displayed for longer matches.
+const MULTI_LINE_TAIL_ROWS: usize = 2;
+
+
+/// Options controlling the terminal reporting of lint matches.
+#[derive(Clone, Debug, Default)]
+pub struct ReportOpts {
+    /// Whether to highlight parts of the report using ANSI color
+    /// escape sequences.
+    pub color: bool,
+    /// The number of lines of context to show before and after the
+    /// code of a match.
+    pub context: usize,
+    /// The struct is non-exhaustive and open to extension.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+
+/// ANSI escape sequences used for highlighting the various parts of a
+/// report.
+struct Style {
+    /// The style used for the severity and code markers.
+    severity: &'static str,
+    /// The style used for the lint name and message.
+    emphasis: &'static str,
+    /// The style used for the left margin and location arrow.
+    gutter: &'static str,
+    /// The sequence resetting any styling.
+    reset: &'static str,
+}
+
+impl Style {
+    fn new(color: bool, severity: Severity) -> Self {
+        if !color {
+            return Self {
+                severity: "",
+                emphasis: "",
+                gutter: "",
+                reset: "",
+            }
+        }
+
+        Self {
+            severity: match severity {
+                Severity::Hint => "\x1b[1;36m",
+                Severity::Info => "\x1b[1;32m",
+                Severity::Warning => "\x1b[1;33m",
+                Severity::Error => "\x1b[1;31m",
+            },
+            emphasis: "\x1b[1m",
+            gutter: "\x1b[1;34m",
+            reset: "\x1b[0m",
+        }
+    }
+}
+
+
+/// Prepare a line of code for display, expanding tabs.
+fn display_line(line: &[u8]) -> String {
+    String::from_utf8_lossy(line).replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// Calculate the number of columns the byte range `bytes` of `line`
+/// occupies when displayed, accounting for tabs and wide characters.
+fn display_width<R>(line: &[u8], bytes: R) -> usize
+where
+    R: SliceIndex<[u8], Output = [u8]>,
+{
+    let bytes = line.get(bytes).unwrap_or_default();
+    String::from_utf8_lossy(bytes)
+        .chars()
+        .map(|c| match c {
+            '\t' => TAB_WIDTH,
+            c => c.width().unwrap_or(0),
+        })
+        .sum()
+}
+
+/// Report a lint match in terminal style.
+///
+/// Line and column numbers are 1-based, with columns being counted in
+/// bytes.
+///
+/// - `match` is the match to create a report for
+/// - `code` is the source code in question, as passed to
+///   [`lint`][crate::lint()]
+/// - `path` should be the path to the file to which `code` corresponds
+///   and is used to enhance the generated report
+/// - `writer` is a reference to a [`io::Write`] to which to write the
+///   report
+///
+/// # Example
+/// ```text
+/// warning: [probe-read] bpf_probe_read() is deprecated and replaced by
+///          bpf_probe_user() and bpf_probe_kernel(); refer to bpf-helpers(7)
+///   --> example.bpf.c:44:25
+///    |
+/// 44 |                         bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
+///    |                         ^^^^^^^^^^^^^^
+///    |
+/// ```
+///
+/// Matches spanning multiple lines are marked in the left margin, with
+/// lines in the middle of long matches being elided:
+/// ```text
+/// warning: [untyped-map-member] ...
+///   --> example.bpf.c:11:1
+///    |
+/// 11 | / struct {
+/// 12 | |     __uint(type, BPF_MAP_TYPE_HASH);
+/// 13 | |     __uint(max_entries, 1024);
+///    | | ...
+/// 16 | |     __type(value, u64);
+/// 17 | | } map SEC(".maps");
+///    | |_^
+///    |
+/// ```
+pub fn report_terminal(
+    r#match: &LintMatch,
+    code: &[u8],
+    path: &Path,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    report_terminal_opts(r#match, code, path, &ReportOpts::default(), writer)
+}
+
+/// Report a lint match in terminal style, as configured by the
+/// provided options.
+///
+/// Please refer to [`report_terminal`] for details.
+pub fn report_terminal_opts(
+    r#match: &LintMatch,
+    code: &[u8],
+    path: &Path,
+    opts: &ReportOpts,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    let LintMatch {
+        lint_name,
+        message,
+        severity,
+        range,
+    } = r#match;
+    let style = Style::new(opts.color, *severity);
+    let Style {
+        severity: sev,
+        emphasis,
+        gutter,
+        reset,
+    } = &style;
+
+    writeln!(
+        writer,
+        "{sev}{severity}{reset}{emphasis}: [{lint_name}] {message}{reset}"
+    )?;
+    let row = range.start_point.row + 1;
+    let col = range.start_point.col + 1;
+    writeln!(
+        writer,
+        "  {gutter}-->{reset} {}:{row}:{col}",
+        path.display()
+    )?;
+
+    if range.is_file_level() {
+        return Ok(())
+    }
+
+    let lines = code.split(|&b| b == b'\n').collect::<Vec<_>>();
+    // A trailing newline does not start another line worth showing.
+    let line_count = lines.len() - usize::from(code.ends_with(b"\n"));
+    let start_row = range.start_point.row;
+    let end_row = range.end_point.row;
+    let before = start_row.saturating_sub(opts.context)..start_row;
+    let after = end_row + 1..(end_row + 1 + opts.context).min(line_count);
+
+    let width = after.end.max(end_row + 1).to_string().len();
+    let prefix = format!("{gutter}{:width$} |{reset} ", "");
+    let lprefix = |row: usize| format!("{gutter}{:>width$} |{reset} ", row + 1);
+    // Lines of multi-line matches are marked in an additional margin
+    // column, which context lines leave empty.
+    let margin = if start_row == end_row { "" } else { "  " };
+
+    writeln!(writer, "{prefix}")?;
+    for row in before {
+        writeln!(
+            writer,
+            "{}{margin}{}",
+            lprefix(row),
+            display_line(lines[row])
+        )?;
+    }
+
+    if start_row == end_row {
+        let line = lines.get(start_row).copied().unwrap_or_default();
+        writeln!(writer, "{}{}", lprefix(start_row), display_line(line))?;
+        let indent = display_width(line, ..range.start_point.col);
+        // Empty ranges mark an insertion point, which we highlight
+        // with a single marker.
+        let width = display_width(line, range.start_point.col..range.end_point.col).max(1);
+        writeln!(writer, "{prefix}{:indent$}{sev}{:^<width$}{reset}", "", "")?;
+    } else {
+        let () = report_multi_line(range, &lines, &prefix, &lprefix, &style, writer)?;
+    }
+
+    for row in after {
+        writeln!(
+            writer,
+            "{}{margin}{}",
+            lprefix(row),
+            display_line(lines[row])
+        )?;
+    }
+    writeln!(writer, "{prefix}")?;
+    Ok(())
+}
+
+/// Report the code snippet of a lint match spanning multiple lines,
+/// marking the covered lines in the left margin.
+fn report_multi_line(
+    range: &Range,
+    lines: &[&[u8]],
+    prefix: &str,
+    lprefix: &dyn Fn(usize) -> String,
+    style: &Style,
+    writer: &mut dyn io::Write,
+) -> Result<()> {
+    let Style {
+        severity: sev,
+        reset,
+        ..
+    } = style;
+    let start_row = range.start_point.row;
+    let end_row = range.end_point.row;
+    let rows = end_row - start_row + 1;
+
+    let mut end_col = 0;
+    let lines = lines.iter().enumerate().skip(start_row).take(rows);
+    for (row, line) in lines {
+        let idx = row - start_row;
+        if rows > MULTI_LINE_MAX_ROWS
+            && idx >= MULTI_LINE_HEAD_ROWS
+            && idx < rows - MULTI_LINE_TAIL_ROWS
+        {
+            if idx == MULTI_LINE_HEAD_ROWS {
+                writeln!(writer, "{prefix}{sev}|{reset} ...")?;
+            }
+            continue
+        }
+
+        let lprefix = lprefix(row);
+        if row == start_row {
+            // If the match starts at the first non-white space
+            // character of the line, we mark the start in the margin
+            // directly. Otherwise we point to the first character.
+            let start_col = range.start_point.col;
+            if line.iter().take(start_col).all(u8::is_ascii_whitespace) {
+                writeln!(writer, "{lprefix}{sev}/{reset} {}", display_line(line))?;
+            } else {
+                writeln!(writer, "{lprefix}  {}", display_line(line))?;
+                let count = display_width(line, ..start_col) + 1;
+                writeln!(writer, "{prefix} {sev}{:_<count$}^{reset}", "")?;
+            }
+        } else {
+            writeln!(writer, "{lprefix}{sev}|{reset} {}", display_line(line))?;
+        }
+
+        if row == end_row {
+            end_col = display_width(line, ..range.end_point.col);
+        }
+    }
+
+    writeln!(
+        writer,
+        "{prefix}{sev}|{:_<count$}^{reset}",
+        "",
+        count = end_col.max(1)
+    )?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::Point;
+
+
+    /// Tests that a match with an empty range includes no code snippet.
+    #[test]
+    fn empty_range_reporting() {
+        let code = indoc! { r#"
+          int main() {}
+        "# };
+
+        let m = LintMatch {
+            lint_name: "bogus-file-extension".to_string(),
+            message: "by convention BPF C code should use the file extension '.bpf.c'".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: 0..0,
+                start_point: Point::default(),
+                end_point: Point::default(),
+            },
+        };
+        let mut report = Vec::new();
+        let () =
+            report_terminal(&m, code.as_bytes(), Path::new("./no_bytes.c"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          warning: [bogus-file-extension] by convention BPF C code should use the file extension '.bpf.c'
+            --> ./no_bytes.c:1:1
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Tests that a match with an empty range inside the code is
+    /// highlighted as an insertion point.
+    #[test]
+    fn insertion_point_reporting() {
+        let code = indoc! { r#"
+          int main() {
+              return 0
+          }
+        "# };
+
+        let start = code.find('0').unwrap() + 1;
+        let m = LintMatch {
+            lint_name: "syntax-error".to_string(),
+            message: "missing code".to_string(),
+            severity: Severity::Info,
+            range: Range {
+                bytes: start..start,
+                start_point: Point { row: 1, col: 12 },
+                end_point: Point { row: 1, col: 12 },
+            },
+        };
+        let mut report = Vec::new();
+        let () =
+            report_terminal(&m, code.as_bytes(), Path::new("./missing.c"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          info: [syntax-error] missing code
+            --> ./missing.c:2:13
+            | 
+          2 |     return 0
+            |             ^
+            | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that our "terminal" reporting works as expected.
+    #[test]
+    fn terminal_reporting() {
+        let code = indoc! { r#"
+          SEC("tp_btf/sched_switch")
+          int handle__sched_switch(u64 *ctx)
+          {
+              struct task_struct *prev = (struct task_struct *)ctx[1];
+              struct event event = {0};
+              bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
+              return 0;
+          }
+        "# };
+
+        let m = LintMatch {
+            lint_name: "probe-read".to_string(),
+            message: "bpf_probe_read() is deprecated".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: 160..174,
+                start_point: Point { row: 5, col: 4 },
+                end_point: Point { row: 5, col: 18 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          warning: [probe-read] bpf_probe_read() is deprecated
+            --> <stdin>:6:5
+            | 
+          6 |     bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
+            |     ^^^^^^^^^^^^^^
+            | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that the match's severity is reflected in the report.
+    #[test]
+    fn severity_reporting() {
+        let code = indoc! { r#"
+          foo();
+        "# };
+
+        let m = LintMatch {
+            lint_name: "foo".to_string(),
+            message: "foo is bad".to_string(),
+            severity: Severity::Error,
+            range: Range {
+                bytes: 0..3,
+                start_point: Point { row: 0, col: 0 },
+                end_point: Point { row: 0, col: 3 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          error: [foo] foo is bad
+            --> <stdin>:1:1
+            | 
+          1 | foo();
+            | ^^^
+            | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that reporting works properly when the match is on the
+    /// very first line of input.
+    #[test]
+    fn report_top_most_line() {
+        let code = indoc! { r#"
+          SEC("kprobe/test")
+          int handle__test(void)
+          {
+          }
+        "# };
+
+        let m = LintMatch {
+            lint_name: "unstable-attach-point".to_string(),
+            message: "kprobe/kretprobe/fentry/fexit are unstable".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: 4..17,
+                start_point: Point { row: 0, col: 4 },
+                end_point: Point { row: 0, col: 17 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          warning: [unstable-attach-point] kprobe/kretprobe/fentry/fexit are unstable
+            --> <stdin>:1:5
+            | 
+          1 | SEC("kprobe/test")
+            |     ^^^^^^^^^^^^^
+            | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that we report matches spanning multiple lines, starting
+    /// at the beginning of a line.
+    #[test]
+    fn multi_line_reporting() {
+        let code = indoc! { r#"
+          /* a map */
+          struct {
+              __uint(type, BPF_MAP_TYPE_HASH);
+          } map SEC(".maps");
+        "# };
+
+        let m = LintMatch {
+            lint_name: "untyped-map-member".to_string(),
+            message: "map is untyped".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: 12..59,
+                start_point: Point { row: 1, col: 0 },
+                end_point: Point { row: 3, col: 1 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          warning: [untyped-map-member] map is untyped
+            --> <stdin>:2:1
+            | 
+          2 | / struct {
+          3 | |     __uint(type, BPF_MAP_TYPE_HASH);
+          4 | | } map SEC(".maps");
+            | |_^
+            | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that we report matches spanning multiple lines, starting
+    /// in the middle of a line.
+    #[test]
+    fn multi_line_reporting_mid_line() {
+        let code = indoc! { r#"
+          int x = foo(
+              1,
+          );
+        "# };
+
+        let m = LintMatch {
+            lint_name: "foo".to_string(),
+            message: "foo is bad".to_string(),
+            severity: Severity::Error,
+            range: Range {
+                bytes: 8..26,
+                start_point: Point { row: 0, col: 8 },
+                end_point: Point { row: 2, col: 1 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          error: [foo] foo is bad
+            --> <stdin>:1:9
+            | 
+          1 |   int x = foo(
+            |  _________^
+          2 | |     1,
+          3 | | );
+            | |_^
+            | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that lines in the middle of long multi-line matches are
+    /// elided.
+    #[test]
+    fn multi_line_truncation() {
+        let code = (0..20).map(|i| format!("line{i}\n")).collect::<String>();
+
+        let m = LintMatch {
+            lint_name: "foo".to_string(),
+            message: "foo is bad".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: 12..67,
+                start_point: Point { row: 2, col: 0 },
+                end_point: Point { row: 11, col: 6 },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          warning: [foo] foo is bad
+            --> <stdin>:3:1
+             | 
+           3 | / line2
+           4 | | line3
+           5 | | line4
+             | | ...
+          11 | | line10
+          12 | | line11
+             | |______^
+             | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that the code snippet's underline accounts for tabs and
+    /// non-ASCII characters.
+    #[test]
+    fn tab_and_utf8_reporting() {
+        let code = "\t/* äöü */ foo();\n";
+        let start = code.find("foo").unwrap();
+        let m = LintMatch {
+            lint_name: "foo".to_string(),
+            message: "foo is bad".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: start..start + 3,
+                start_point: Point { row: 0, col: start },
+                end_point: Point {
+                    row: 0,
+                    col: start + 3,
+                },
+            },
+        };
+        let mut report = Vec::new();
+        let () = report_terminal(&m, code.as_bytes(), Path::new("<stdin>"), &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          warning: [foo] foo is bad
+            --> <stdin>:1:15
+            | 
+          1 |     /* äöü */ foo();
+            |               ^^^
+            | 
+        "# };
+        assert_eq!(report, expected);
+    }
+
+    /// Check that we highlight parts of the report when asked to.
+    #[test]
+    fn color_reporting() {
+        let code = indoc! { r#"
+          foo();
+        "# };
+
+        let m = LintMatch {
+            lint_name: "foo".to_string(),
+            message: "foo is bad".to_string(),
+            severity: Severity::Error,
+            range: Range {
+                bytes: 0..3,
+                start_point: Point { row: 0, col: 0 },
+                end_point: Point { row: 0, col: 3 },
+            },
+        };
+        let opts = ReportOpts {
+            color: true,
+            ..Default::default()
+        };
+        let mut report = Vec::new();
+        let () = report_terminal_opts(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &opts,
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = "\
+\x1b[1;31merror\x1b[0m\x1b[1m: [foo] foo is bad\x1b[0m
+  \x1b[1;34m-->\x1b[0m <stdin>:1:1
+\x1b[1;34m  |\x1b[0m 
+\x1b[1;34m1 |\x1b[0m foo();
+\x1b[1;34m  |\x1b[0m \x1b[1;31m^^^\x1b[0m
+\x1b[1;34m  |\x1b[0m 
+";
+        assert_eq!(report, expected);
+
+        // Without color, the report should not contain any escape
+        // sequences.
+        let mut report = Vec::new();
+        let () = report_terminal_opts(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &ReportOpts::default(),
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(!report.contains('\x1b'), "{report}");
+    }
+
+    /// Check that we show the requested number of context lines around
+    /// a match.
+    #[test]
+    fn context_reporting() {
+        let code = indoc! { r#"
+          /* comment */
+          SEC("kprobe/test")
+          int handle__test(void)
+          {
+          }
+        "# };
+
+        let m = LintMatch {
+            lint_name: "unstable-attach-point".to_string(),
+            message: "kprobe/kretprobe/fentry/fexit are unstable".to_string(),
+            severity: Severity::Warning,
+            range: Range {
+                bytes: 18..31,
+                start_point: Point { row: 1, col: 4 },
+                end_point: Point { row: 1, col: 17 },
+            },
+        };
+        let opts = ReportOpts {
+            context: 2,
+            ..Default::default()
+        };
+        let mut report = Vec::new();
+        let () = report_terminal_opts(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &opts,
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          warning: [unstable-attach-point] kprobe/kretprobe/fentry/fexit are unstable
+            --> <stdin>:2:5
+            | 
+          1 | /* comment */
+          2 | SEC("kprobe/test")
+            |     ^^^^^^^^^^^^^
+          3 | int handle__test(void)
+          4 | {
+            | 
+        "# };
+        assert_eq!(report, expected);
+
+        // Context should not extend past the end of the input.
+        let m = LintMatch {
+            range: Range {
+                bytes: 52..53,
+                start_point: Point { row: 3, col: 0 },
+                end_point: Point { row: 4, col: 1 },
+            },
+            ..m
+        };
+        let opts = ReportOpts {
+            context: 1,
+            ..Default::default()
+        };
+        let mut report = Vec::new();
+        let () = report_terminal_opts(
+            &m,
+            code.as_bytes(),
+            Path::new("<stdin>"),
+            &opts,
+            &mut report,
+        )
+        .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        let expected = indoc! { r#"
+          warning: [unstable-attach-point] kprobe/kretprobe/fentry/fexit are unstable
+            --> <stdin>:4:1
+            | 
+          3 |   int handle__test(void)
+          4 | / {
+          5 | | }
+            | |_^
+            | 
+        "# };
+        assert_eq!(report, expected);
+    }
+}
@@ -7,5 +7,7 @@ mod validate;
 
 #[path = "probe-read.rs"]
 mod probe_read;
+#[path = "syntax-error.rs"]
+mod syntax_error;
 #[path = "unstable-attach-point.rs"]
 mod unstable_attach_point;
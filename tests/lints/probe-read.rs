@@ -22,11 +22,34 @@ fn basic() {
 
     let expected = indoc! { r#"
       warning: [probe-read] bpf_probe_read() is deprecated and replaced by bpf_probe_user() and bpf_probe_kernel(); refer to bpf-helpers(7)
-        --> <stdin>:5:4
+        --> <stdin>:6:5
         | 
-      5 |     bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
+      6 |     bpf_probe_read(event.comm, TASK_COMM_LEN, prev->comm);
         |     ^^^^^^^^^^^^^^
         | 
     "# };
     assert_eq!(lint_report(code), expected);
 }
+
+#[test]
+fn probe_read_str() {
+    let code = indoc! { r#"
+      SEC("tp_btf/sched_process_exec")
+      int handle__sched_process_exec(u64 *ctx)
+      {
+          struct event event = {0};
+          bpf_probe_read_str(event.filename, sizeof(event.filename), ctx[2]);
+          return 0;
+      }
+    "# };
+
+    let expected = indoc! { r#"
+      warning: [probe-read] bpf_probe_read_str() is deprecated and replaced by bpf_probe_read_user_str() and bpf_probe_read_kernel_str(); refer to bpf-helpers(7)
+        --> <stdin>:5:5
+        | 
+      5 |     bpf_probe_read_str(event.filename, sizeof(event.filename), ctx[2]);
+        |     ^^^^^^^^^^^^^^^^^^
+        | 
+    "# };
+    assert_eq!(lint_report(code), expected);
+}
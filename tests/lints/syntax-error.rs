@@ -0,0 +1,82 @@
+//! Tests for the `syntax-error` lint.
+
+use indoc::indoc;
+
+use pretty_assertions::assert_eq;
+
+use bpflint::has_syntax_errors;
+
+use crate::util::lint_report;
+use crate::util::lint_report_with;
+
+
+/// Check that we report code that could not be parsed.
+#[test]
+fn error() {
+    let code = indoc! { r#"
+      int main(void)
+      {
+          int x = 1 2 3;
+          return x;
+      }
+    "# };
+
+    let report = lint_report_with(&["syntax-error"], code);
+    assert!(
+        report.starts_with(
+            "info: [syntax-error] syntax error; the affected code may not be covered by lints\n"
+        ),
+        "{report}"
+    );
+    assert!(report.contains("  --> <stdin>:3:"), "{report}");
+    assert!(has_syntax_errors(code.as_bytes()).unwrap());
+}
+
+/// Check that we report code that could only be parsed by assuming
+/// missing code.
+#[test]
+fn missing() {
+    let code = indoc! { r#"
+      int main(void)
+      {
+          return 0
+      }
+    "# };
+
+    let expected = indoc! { r#"
+      info: [syntax-error] syntax error due to missing code; the surrounding code may not be covered by lints
+        --> <stdin>:3:13
+        | 
+      3 |     return 0
+        |             ^
+        | 
+    "# };
+    assert_eq!(lint_report_with(&["syntax-error"], code), expected);
+    assert!(has_syntax_errors(code.as_bytes()).unwrap());
+}
+
+/// Make sure that we do not report anything for code that parses
+/// cleanly.
+#[test]
+fn no_error() {
+    let code = indoc! { r#"
+      int main(void)
+      {
+          return 0;
+      }
+    "# };
+
+    assert_eq!(lint_report_with(&["syntax-error"], code), "");
+    assert!(!has_syntax_errors(code.as_bytes()).unwrap());
+}
+
+/// Check that the lint is not part of the default set of lints.
+#[test]
+fn disabled_by_default() {
+    let code = indoc! { r#"
+      int x = ;
+    "# };
+
+    assert_eq!(lint_report(code), "");
+    assert!(has_syntax_errors(code.as_bytes()).unwrap());
+}
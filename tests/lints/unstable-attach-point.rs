@@ -17,9 +17,9 @@ fn basic() {
 
     let expected = indoc! { r#"
       warning: [unstable-attach-point] kprobe/kretprobe/fentry/fexit are conceptually unstable and prone to changes between kernel versions; consider more stable attach points such as tracepoints or LSM hooks, if available
-        --> <stdin>:0:4
+        --> <stdin>:1:5
         | 
-      0 | SEC("fentry/do_nanosleep")
+      1 | SEC("fentry/do_nanosleep")
         |     ^^^^^^^^^^^^^^^^^^^^^
         | 
     "# };
@@ -38,9 +38,9 @@ fn basic2() {
 
     let expected = indoc! { r#"
       warning: [unstable-attach-point] kprobe/kretprobe/fentry/fexit are conceptually unstable and prone to changes between kernel versions; consider more stable attach points such as tracepoints or LSM hooks, if available
-        --> <stdin>:0:4
+        --> <stdin>:1:5
         | 
-      0 | SEC("kprobe/cap_capable")
+      1 | SEC("kprobe/cap_capable")
         |     ^^^^^^^^^^^^^^^^^^^^
         | 
     "# };
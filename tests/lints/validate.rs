@@ -85,6 +85,41 @@ fn builtin_lint_names() {
 }
 
 
+/// Check that all built-in lints come with the expected meta data.
+#[test]
+fn builtin_lint_meta() {
+    for LintMeta {
+        name,
+        summary,
+        rationale,
+        references,
+        examples,
+        ..
+    } in builtin_lints()
+    {
+        assert!(!summary.is_empty(), "lint `{name}` lacks a summary");
+        assert!(
+            !summary.contains('\n'),
+            "summary of lint `{name}` spans multiple lines"
+        );
+        assert!(!rationale.is_empty(), "lint `{name}` lacks a rationale");
+        assert!(
+            references
+                .iter()
+                .all(|reference| reference.starts_with("https://")),
+            "lint `{name}` has invalid reference: {references:?}"
+        );
+        assert!(!examples.is_empty(), "lint `{name}` lacks examples");
+        for example in examples {
+            assert!(
+                !example.bad.is_empty() && !example.good.is_empty(),
+                "lint `{name}` has incomplete example: {example:?}"
+            );
+        }
+    }
+}
+
+
 /// Make sure that each lint has a test module associated with it.
 #[test]
 fn lint_test_assurance() {
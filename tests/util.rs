@@ -2,22 +2,37 @@
 
 use std::path::Path;
 
+use bpflint::LintMatch;
+use bpflint::Linter;
 use bpflint::lint;
 use bpflint::report_terminal;
 
 
+fn report(matches: Vec<LintMatch>, code: &[u8]) -> String {
+    let mut report = Vec::new();
+    let () = matches
+        .into_iter()
+        .try_for_each(|m| report_terminal(&m, code, Path::new("<stdin>"), &mut report))
+        .unwrap();
+    let report = String::from_utf8(report).unwrap();
+    report
+}
+
 /// Lint `code` and report matches as a string created using
 /// [`report_terminal`].
 pub fn lint_report<C>(code: C) -> String
 where
     C: AsRef<[u8]>,
 {
-    let mut report = Vec::new();
-    let () = lint(code.as_ref())
-        .unwrap()
-        .into_iter()
-        .try_for_each(|m| report_terminal(&m, code.as_ref(), Path::new("<stdin>"), &mut report))
-        .unwrap();
-    let report = String::from_utf8(report).unwrap();
-    report
+    report(lint(code.as_ref()).unwrap(), code.as_ref())
+}
+
+/// Lint `code` using only the built-in lints `names` and report matches
+/// as a string created using [`report_terminal`].
+pub fn lint_report_with<C>(names: &[&str], code: C) -> String
+where
+    C: AsRef<[u8]>,
+{
+    let linter = Linter::with_lints(names).unwrap();
+    report(linter.lint(code.as_ref()).unwrap(), code.as_ref())
 }